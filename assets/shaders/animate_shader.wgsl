@@ -33,8 +33,23 @@ struct Time {
 @group(2) @binding(0)
 var<uniform> time: Time;
 
+// mouse button bits
+let MOUSE_LEFT: u32 = 1u;
+let MOUSE_RIGHT: u32 = 2u;
+let MOUSE_MIDDLE: u32 = 4u;
+let MOUSE_OTHER: u32 = 8u;
+
 struct Mouse {
     normalised_device_coordinates: vec2<f32>,
+    motion: vec2<f32>,
+    wheel: vec2<f32>,
+    wheel_delta: vec2<f32>,
+    drag_start: vec2<f32>,
+    drag_delta: vec2<f32>,
+    pressed: u32,
+    just_pressed: u32,
+    just_released: u32,
+    dragging: u32,
 }
 @group(3) @binding(0)
 var<uniform> mouse: Mouse;
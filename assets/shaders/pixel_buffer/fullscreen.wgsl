@@ -0,0 +1,26 @@
+@group(0) @binding(0)
+var buffer_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var buffer_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// Fullscreen triangle, as `vs_main_trig` in compute_rasterizer/present_pass.wgsl,
+// with the uvs flipped so (0, 0) is the top left of the buffer.
+// The viewport set by the pass limits it to the letterboxed area.
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_idx: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_idx << 1u) & 2u), f32(vertex_idx & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(buffer_texture, buffer_sampler, in.uv);
+}
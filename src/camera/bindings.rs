@@ -0,0 +1,494 @@
+//! Input bindings of the [OrbitCamera](super::pan_orbit_camera::OrbitCamera).
+//!
+//! Each camera action is bound to any number of mouse, keyboard and gamepad
+//! inputs with a [CameraBindings] component on the camera entity. Cameras without
+//! one use [CameraBindings::default].
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::mouse::{
+        MouseMotion,
+        MouseScrollUnit::{Line, Pixel},
+        MouseWheel,
+    },
+    prelude::*,
+};
+
+use super::pan_orbit_camera::{AlignedView, ControllerMode};
+
+const LINE_TO_PIXEL_RATIO: f32 = 0.1;
+
+/// Modifier keys that must be held, and no others, for a binding to be active
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    /// Either shift key
+    pub shift: bool,
+    /// Either control key
+    pub ctrl: bool,
+    /// Either alt key
+    pub alt: bool,
+}
+
+impl Modifiers {
+    /// No modifiers
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+    };
+    /// Only shift
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ctrl: false,
+        alt: false,
+    };
+    /// Only control
+    pub const CTRL: Modifiers = Modifiers {
+        shift: false,
+        ctrl: true,
+        alt: false,
+    };
+
+    /// The held modifiers
+    fn held(keys: &Input<KeyCode>) -> Self {
+        Modifiers {
+            shift: keys.any_pressed([KeyCode::LShift, KeyCode::RShift]),
+            ctrl: keys.any_pressed([KeyCode::LControl, KeyCode::RControl]),
+            alt: keys.any_pressed([KeyCode::LAlt, KeyCode::RAlt]),
+        }
+    }
+}
+
+/// Gamepad stick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadStick {
+    /// Left stick
+    Left,
+    /// Right stick
+    Right,
+}
+
+/// Input bound to a movement of the camera.
+///
+/// Values are in screen directions with y down, like mouse motion. The single axis
+/// bindings only set y, positive zooms in when bound to [CameraBindings::zoom].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AxisBinding {
+    /// Dragging the mouse with a button held
+    MouseDrag {
+        /// Held button
+        button: MouseButton,
+        /// Held modifiers
+        modifiers: Modifiers,
+    },
+    /// Scrolling the mouse wheel, single axis
+    MouseWheel {
+        /// Held modifiers
+        modifiers: Modifiers,
+    },
+    /// Four keys moving in the screen directions
+    Keys {
+        /// Moves up
+        up: KeyCode,
+        /// Moves down
+        down: KeyCode,
+        /// Moves left
+        left: KeyCode,
+        /// Moves right
+        right: KeyCode,
+    },
+    /// Two keys, single axis
+    KeyAxis {
+        /// Moves positive
+        positive: KeyCode,
+        /// Moves negative
+        negative: KeyCode,
+    },
+    /// A gamepad stick
+    GamepadStick(GamepadStick),
+    /// Two analog gamepad buttons such as the triggers, single axis
+    GamepadTriggers {
+        /// Moves positive
+        positive: GamepadButtonType,
+        /// Moves negative
+        negative: GamepadButtonType,
+    },
+}
+
+/// Input bound to an instant action of the camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonBinding {
+    /// A key, pressed without modifiers
+    Key(KeyCode),
+    /// A gamepad button of any gamepad
+    GamepadButton(GamepadButtonType),
+}
+
+/// Component with the input bindings of a camera.
+///
+/// The orbit, pan and zoom bindings are read in [ControllerMode::Orbit], the look
+/// and movement ones in the fly and walk modes.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct CameraBindings {
+    /// Bindings that orbit around the origin
+    pub orbit: Vec<AxisBinding>,
+    /// Bindings that pan the origin
+    pub pan: Vec<AxisBinding>,
+    /// Bindings that zoom to the origin
+    pub zoom: Vec<AxisBinding>,
+    /// Bindings that turn the camera in place
+    pub look: Vec<AxisBinding>,
+    /// Bindings that move forward, back and sideways. Mouse bindings are ignored.
+    pub movement: Vec<AxisBinding>,
+    /// Bindings that move up and down in fly mode, single axis. Mouse bindings are
+    /// ignored.
+    pub vertical: Vec<AxisBinding>,
+    /// Bindings that switch the controller mode
+    pub modes: Vec<(ButtonBinding, ControllerMode)>,
+    /// Bindings that move the camera back to where it started
+    pub reset: Vec<ButtonBinding>,
+    /// Bindings that switch between perspective and orthographic projection
+    pub toggle_projection: Vec<ButtonBinding>,
+    /// Bindings that look along the world axes
+    pub aligned_views: Vec<(ButtonBinding, AlignedView)>,
+    /// Speed of the keys and sticks when orbiting and panning, in mouse pixels per second
+    pub axis_speed: f32,
+    /// Speed of the keys and triggers when zooming, in wheel lines per second
+    pub zoom_speed: f32,
+}
+
+impl Default for CameraBindings {
+    fn default() -> Self {
+        Self {
+            orbit: vec![
+                AxisBinding::MouseDrag {
+                    button: MouseButton::Left,
+                    modifiers: Modifiers::NONE,
+                },
+                AxisBinding::Keys {
+                    up: KeyCode::W,
+                    down: KeyCode::S,
+                    left: KeyCode::A,
+                    right: KeyCode::D,
+                },
+                AxisBinding::GamepadStick(GamepadStick::Right),
+            ],
+            pan: vec![
+                AxisBinding::MouseDrag {
+                    button: MouseButton::Right,
+                    modifiers: Modifiers::NONE,
+                },
+                AxisBinding::MouseDrag {
+                    button: MouseButton::Left,
+                    modifiers: Modifiers::SHIFT,
+                },
+                AxisBinding::Keys {
+                    up: KeyCode::Up,
+                    down: KeyCode::Down,
+                    left: KeyCode::Left,
+                    right: KeyCode::Right,
+                },
+                AxisBinding::GamepadStick(GamepadStick::Left),
+            ],
+            zoom: vec![
+                AxisBinding::MouseWheel {
+                    modifiers: Modifiers::NONE,
+                },
+                AxisBinding::KeyAxis {
+                    positive: KeyCode::Equals,
+                    negative: KeyCode::Minus,
+                },
+                AxisBinding::KeyAxis {
+                    positive: KeyCode::NumpadAdd,
+                    negative: KeyCode::NumpadSubtract,
+                },
+                AxisBinding::GamepadTriggers {
+                    positive: GamepadButtonType::RightTrigger2,
+                    negative: GamepadButtonType::LeftTrigger2,
+                },
+            ],
+            look: vec![
+                AxisBinding::MouseDrag {
+                    button: MouseButton::Left,
+                    modifiers: Modifiers::NONE,
+                },
+                AxisBinding::MouseDrag {
+                    button: MouseButton::Right,
+                    modifiers: Modifiers::NONE,
+                },
+                AxisBinding::GamepadStick(GamepadStick::Right),
+            ],
+            movement: vec![
+                AxisBinding::Keys {
+                    up: KeyCode::W,
+                    down: KeyCode::S,
+                    left: KeyCode::A,
+                    right: KeyCode::D,
+                },
+                AxisBinding::Keys {
+                    up: KeyCode::Up,
+                    down: KeyCode::Down,
+                    left: KeyCode::Left,
+                    right: KeyCode::Right,
+                },
+                AxisBinding::GamepadStick(GamepadStick::Left),
+            ],
+            vertical: vec![
+                AxisBinding::KeyAxis {
+                    positive: KeyCode::E,
+                    negative: KeyCode::Q,
+                },
+                AxisBinding::GamepadTriggers {
+                    positive: GamepadButtonType::RightTrigger2,
+                    negative: GamepadButtonType::LeftTrigger2,
+                },
+            ],
+            modes: vec![
+                (ButtonBinding::Key(KeyCode::F1), ControllerMode::Orbit),
+                (ButtonBinding::Key(KeyCode::F2), ControllerMode::Fly),
+                (ButtonBinding::Key(KeyCode::F3), ControllerMode::Walk),
+            ],
+            reset: vec![
+                ButtonBinding::Key(KeyCode::Home),
+                ButtonBinding::GamepadButton(GamepadButtonType::Select),
+            ],
+            toggle_projection: vec![ButtonBinding::Key(KeyCode::Numpad5)],
+            aligned_views: vec![
+                (ButtonBinding::Key(KeyCode::Numpad1), AlignedView::Front),
+                (ButtonBinding::Key(KeyCode::Numpad3), AlignedView::Right),
+                (ButtonBinding::Key(KeyCode::Numpad7), AlignedView::Top),
+                (ButtonBinding::Key(KeyCode::Numpad9), AlignedView::Bottom),
+            ],
+            axis_speed: 400.0,
+            zoom_speed: 5.0,
+        }
+    }
+}
+
+impl CameraBindings {
+    /// Bindings without any input
+    pub fn empty() -> Self {
+        Self {
+            orbit: Vec::new(),
+            pan: Vec::new(),
+            zoom: Vec::new(),
+            look: Vec::new(),
+            movement: Vec::new(),
+            vertical: Vec::new(),
+            modes: Vec::new(),
+            reset: Vec::new(),
+            toggle_projection: Vec::new(),
+            aligned_views: Vec::new(),
+            ..Default::default()
+        }
+    }
+}
+
+/// System parameter with the inputs the [CameraBindings] read.
+#[derive(SystemParam)]
+pub struct CameraInputs<'w, 's> {
+    keys: Res<'w, Input<KeyCode>>,
+    mouse_buttons: Res<'w, Input<MouseButton>>,
+    mouse_motion: EventReader<'w, 's, MouseMotion>,
+    mouse_wheel: EventReader<'w, 's, MouseWheel>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+    gamepad_button_axes: Res<'w, Axis<GamepadButton>>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+}
+
+/// State of the inputs in a frame
+pub struct InputFrame<'a> {
+    keys: &'a Input<KeyCode>,
+    mouse_buttons: &'a Input<MouseButton>,
+    modifiers: Modifiers,
+    mouse_motion: Vec2,
+    /// In lines
+    mouse_wheel: f32,
+    gamepads: &'a Gamepads,
+    gamepad_axes: &'a Axis<GamepadAxis>,
+    gamepad_button_axes: &'a Axis<GamepadButton>,
+    gamepad_buttons: &'a Input<GamepadButton>,
+    delta_seconds: f32,
+}
+
+impl<'w, 's> CameraInputs<'w, 's> {
+    /// Reads the mouse events of this frame, call once per frame.
+    pub fn frame(&mut self, delta_seconds: f32) -> InputFrame<'_> {
+        let mouse_motion = self
+            .mouse_motion
+            .iter()
+            .fold(Vec2::ZERO, |delta, event| delta + event.delta);
+        let mouse_wheel = self.mouse_wheel.iter().fold(0.0, |total, event| {
+            total
+                + event.y
+                    * match event.unit {
+                        Line => 1.0,
+                        Pixel => LINE_TO_PIXEL_RATIO,
+                    }
+        });
+
+        InputFrame {
+            keys: &self.keys,
+            mouse_buttons: &self.mouse_buttons,
+            modifiers: Modifiers::held(&self.keys),
+            mouse_motion,
+            mouse_wheel,
+            gamepads: &self.gamepads,
+            gamepad_axes: &self.gamepad_axes,
+            gamepad_button_axes: &self.gamepad_button_axes,
+            gamepad_buttons: &self.gamepad_buttons,
+            delta_seconds,
+        }
+    }
+}
+
+/// Value of a binding in a frame
+pub enum AxisValue {
+    /// Mouse motion in pixels
+    Mouse(Vec2),
+    /// Mouse wheel in lines
+    Wheel(f32),
+    /// Keys or gamepad, already scaled by the binding speeds and the frame time
+    Scaled(Vec2),
+}
+
+impl AxisValue {
+    /// The value as mouse motion in pixels, for orbiting and panning
+    pub fn motion(&self) -> Vec2 {
+        match self {
+            AxisValue::Mouse(motion) | AxisValue::Scaled(motion) => *motion,
+            AxisValue::Wheel(lines) => Vec2::new(0.0, *lines / LINE_TO_PIXEL_RATIO),
+        }
+    }
+
+    /// The value as wheel lines, for zooming
+    pub fn lines(&self) -> f32 {
+        match self {
+            // dragging up zooms in
+            AxisValue::Mouse(motion) => -motion.y * LINE_TO_PIXEL_RATIO,
+            AxisValue::Wheel(lines) => *lines,
+            AxisValue::Scaled(value) => value.y,
+        }
+    }
+}
+
+impl<'a> InputFrame<'a> {
+    /// Values of the active bindings. Bindings are active while their input is
+    /// held, even if it does not move.
+    pub fn read(&self, bindings: &[AxisBinding], speeds: &CameraBindings) -> Vec<AxisValue> {
+        let axis_step = speeds.axis_speed * self.delta_seconds;
+        let zoom_step = speeds.zoom_speed * self.delta_seconds;
+        bindings
+            .iter()
+            .filter_map(|binding| self.read_axis(binding, axis_step, zoom_step))
+            .collect()
+    }
+
+    /// Sum of the keys, sticks and triggers of the bindings, each of length 1 at most.
+    /// Mouse bindings are ignored.
+    pub fn direction(&self, bindings: &[AxisBinding]) -> Vec2 {
+        bindings
+            .iter()
+            .filter_map(|binding| match self.read_axis(binding, 1.0, 1.0) {
+                Some(AxisValue::Scaled(value)) => Some(value),
+                _ => None,
+            })
+            .fold(Vec2::ZERO, |direction, value| direction + value)
+    }
+
+    /// The held modifiers
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Any mouse button is held
+    pub fn dragging(&self) -> bool {
+        self.mouse_buttons.get_pressed().next().is_some()
+    }
+
+    fn read_axis(
+        &self,
+        binding: &AxisBinding,
+        axis_step: f32,
+        zoom_step: f32,
+    ) -> Option<AxisValue> {
+        match binding {
+            AxisBinding::MouseDrag { button, modifiers } => (self.mouse_buttons.pressed(*button)
+                && self.modifiers == *modifiers)
+                .then_some(AxisValue::Mouse(self.mouse_motion)),
+            AxisBinding::MouseWheel { modifiers } => (self.mouse_wheel != 0.0
+                && self.modifiers == *modifiers)
+                .then_some(AxisValue::Wheel(self.mouse_wheel)),
+            AxisBinding::Keys {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                let direction = Vec2::new(
+                    self.key_axis(*right, *left),
+                    // screen directions are y down
+                    self.key_axis(*down, *up),
+                );
+                (direction != Vec2::ZERO).then_some(AxisValue::Scaled(direction * axis_step))
+            }
+            AxisBinding::KeyAxis { positive, negative } => {
+                let value = self.key_axis(*positive, *negative);
+                (value != 0.0).then_some(AxisValue::Scaled(Vec2::new(0.0, value * zoom_step)))
+            }
+            AxisBinding::GamepadStick(stick) => {
+                let (x_axis, y_axis) = match stick {
+                    GamepadStick::Left => {
+                        (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY)
+                    }
+                    GamepadStick::Right => {
+                        (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY)
+                    }
+                };
+                let value = self.gamepads.iter().fold(Vec2::ZERO, |value, gamepad| {
+                    let x = self.gamepad_axes.get(GamepadAxis::new(gamepad, x_axis));
+                    let y = self.gamepad_axes.get(GamepadAxis::new(gamepad, y_axis));
+                    // sticks are y up
+                    value + Vec2::new(x.unwrap_or(0.0), -y.unwrap_or(0.0))
+                });
+                (value != Vec2::ZERO).then_some(AxisValue::Scaled(value * axis_step))
+            }
+            AxisBinding::GamepadTriggers { positive, negative } => {
+                let value = self.gamepads.iter().fold(0.0, |value, gamepad| {
+                    let button_value = |button_type| {
+                        self.gamepad_button_axes
+                            .get(GamepadButton::new(gamepad, button_type))
+                            .unwrap_or(0.0)
+                    };
+                    value + button_value(*positive) - button_value(*negative)
+                });
+                (value != 0.0).then_some(AxisValue::Scaled(Vec2::new(0.0, value * zoom_step)))
+            }
+        }
+    }
+
+    fn key_axis(&self, positive: KeyCode, negative: KeyCode) -> f32 {
+        let mut value = 0.0;
+        if self.keys.pressed(positive) {
+            value += 1.0;
+        }
+        if self.keys.pressed(negative) {
+            value -= 1.0;
+        }
+        value
+    }
+
+    /// Any of the buttons was just pressed
+    pub fn just_pressed(&self, bindings: &[ButtonBinding]) -> bool {
+        bindings.iter().any(|binding| match binding {
+            ButtonBinding::Key(key) => {
+                self.keys.just_pressed(*key) && self.modifiers == Modifiers::NONE
+            }
+            ButtonBinding::GamepadButton(button_type) => self.gamepads.iter().any(|gamepad| {
+                self.gamepad_buttons
+                    .just_pressed(GamepadButton::new(gamepad, *button_type))
+            }),
+        })
+    }
+}
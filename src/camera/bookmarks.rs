@@ -0,0 +1,160 @@
+//! Named [OrbitCamera] states.
+//!
+//! [CameraBookmarksPlugin] keeps the bookmarks in a RON file, so viewpoints of a
+//! scene can be shared and survive restarts. [BookmarkEvent]s save the state of a
+//! camera under a name and move a camera back to a saved state with an eased
+//! transition.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::pan_orbit_camera::{ControllerMode, OrbitCamera, OrbitState};
+
+/// Error type of reading and writing [CameraBookmarks]
+#[derive(thiserror::Error, Debug)]
+pub enum BookmarkError {
+    /// The file cannot be read or written
+    #[error("cannot access the bookmarks file: {0}")]
+    Io(#[from] io::Error),
+    /// The file is not valid RON
+    #[error("invalid bookmarks file: {0}")]
+    Ron(#[from] ron::Error),
+}
+
+/// Camera states by name
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmarks {
+    pub bookmarks: BTreeMap<String, OrbitState>,
+}
+
+impl CameraBookmarks {
+    /// Reads bookmarks from a RON file
+    pub fn load(path: &Path) -> Result<Self, BookmarkError> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the bookmarks to a RON file
+    pub fn save(&self, path: &Path) -> Result<(), BookmarkError> {
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        fs::write(path, ron)?;
+        Ok(())
+    }
+}
+
+/// Saves and recalls [CameraBookmarks]
+pub enum BookmarkEvent {
+    /// Saves the current state of a camera
+    Save { camera: Entity, name: String },
+    /// Moves a camera to a saved state in `duration` seconds
+    Recall {
+        camera: Entity,
+        name: String,
+        duration: f32,
+    },
+}
+
+/// [Plugin] loading the [CameraBookmarks] from a file at startup and writing them
+/// back every time one is saved.
+pub struct CameraBookmarksPlugin {
+    pub path: PathBuf,
+}
+
+impl Default for CameraBookmarksPlugin {
+    fn default() -> Self {
+        CameraBookmarksPlugin {
+            path: PathBuf::from("camera_bookmarks.ron"),
+        }
+    }
+}
+
+/// Path of the bookmarks file
+#[derive(Resource)]
+struct BookmarksPath(PathBuf);
+
+impl Plugin for CameraBookmarksPlugin {
+    fn build(&self, app: &mut App) {
+        let bookmarks = if self.path.exists() {
+            CameraBookmarks::load(&self.path).unwrap_or_else(|err| {
+                error!("Cannot load camera bookmarks {:?}: {}", self.path, err);
+                CameraBookmarks::default()
+            })
+        } else {
+            CameraBookmarks::default()
+        };
+
+        app.insert_resource(bookmarks)
+            .insert_resource(BookmarksPath(self.path.clone()))
+            .add_event::<BookmarkEvent>()
+            .add_system(bookmark_system);
+    }
+}
+
+fn bookmark_system(
+    mut events: EventReader<BookmarkEvent>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    path: Res<BookmarksPath>,
+    mut cameras: Query<&mut OrbitCamera>,
+) {
+    let mut saved = false;
+    for event in events.iter() {
+        match event {
+            BookmarkEvent::Save { camera, name } => match cameras.get(*camera) {
+                Ok(orbit_camera) => {
+                    bookmarks
+                        .bookmarks
+                        .insert(name.clone(), orbit_camera.state());
+                    saved = true;
+                }
+                Err(_) => warn!("Cannot bookmark {:?}, it is not an OrbitCamera", camera),
+            },
+            BookmarkEvent::Recall {
+                camera,
+                name,
+                duration,
+            } => match (cameras.get_mut(*camera), bookmarks.bookmarks.get(name)) {
+                (Ok(mut orbit_camera), Some(state)) => {
+                    // bookmarks are orbit states
+                    orbit_camera.mode = ControllerMode::Orbit;
+                    orbit_camera.transition_to(*state, *duration)
+                }
+                (Err(_), _) => warn!("Cannot recall {:?}, it is not an OrbitCamera", camera),
+                (_, None) => warn!("No camera bookmark named {:?}", name),
+            },
+        }
+    }
+
+    if saved {
+        if let Err(err) = bookmarks.save(&path.0) {
+            error!("Cannot save camera bookmarks {:?}: {}", path.0, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmarks_round_trip() {
+        let mut bookmarks = CameraBookmarks::default();
+        bookmarks.bookmarks.insert(
+            "front".to_string(),
+            OrbitState {
+                origin: Vec3::new(1.0, 2.0, 3.0),
+                θ: 0.5,
+                ϕ: 1.0,
+                ρ: 10.0,
+            },
+        );
+
+        let ron =
+            ron::ser::to_string_pretty(&bookmarks, ron::ser::PrettyConfig::default()).unwrap();
+        let loaded: CameraBookmarks = ron::from_str(&ron).unwrap();
+        assert_eq!(loaded, bookmarks);
+    }
+}
@@ -1 +1,3 @@
+pub mod bindings;
+pub mod bookmarks;
 pub mod pan_orbit_camera;
@@ -1,49 +1,307 @@
 use bevy::prelude::*;
-use std::ops::RangeInclusive;
-use bevy::{render::camera::Camera, input::mouse::{MouseMotion, MouseWheel, MouseScrollUnit::{Line, Pixel}}};
+use bevy::{
+    ecs::event::ManualEventReader,
+    render::{
+        camera::{Camera, Projection, RenderTarget, ScalingMode},
+        primitives::Aabb,
+    },
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    f32::consts::{FRAC_PI_2, PI, TAU},
+    ops::{DerefMut, RangeInclusive},
+};
 
-const LINE_TO_PIXEL_RATIO: f32 = 0.1;
+use super::bindings::{AxisValue, CameraBindings, CameraInputs};
+use crate::{
+    events::{gestures::Gesture, Touches},
+    plugins::{
+        mouse::viewport_ndc,
+        picking::{ray_mesh_intersection, Ray},
+    },
+};
 
+/// Distance to the targets under which the camera snaps to them
+const SETTLE_EPSILON: f32 = 1e-4;
+/// Radius in the fly and walk modes, the camera turns around a point just in front
+/// of it
+const FLY_RADIUS: f32 = 0.01;
+/// Orbit angle per pixel of input, in radians. Matches the speed orbiting had when
+/// it depended on a 60 fps frame rate.
+const RADIANS_PER_PIXEL: f32 = 1.0 / 60.0;
+/// Speed factor of the movement while holding shift
+const FAST_FACTOR: f32 = 4.0;
+/// Speed factor of the movement while holding control
+const SLOW_FACTOR: f32 = 0.25;
 
-pub enum CameraEvents {
+/// Camera movement, sent to one camera by the input binding and touch systems.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraEvent {
+    /// Camera entity that moves
+    pub camera: Entity,
+    /// How it moves
+    pub action: CameraAction,
+}
+
+/// Camera movements.
+///
+/// The deltas are already scaled by the sensitivity of the input that sent them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraAction {
+    /// Pan delta in pixels, in screen directions with y down. The point of the
+    /// focal plane under the cursor follows it.
     Pan(Vec2),
+    /// Orbit delta, in screen directions with y down. Turns the camera in place in
+    /// the fly and walk modes.
     Orbit(Vec2),
-    Zoom(f32)
+    /// Movement in the fly and walk modes, in world units along the camera axes with
+    /// x right, y up and z back. Walking ignores y.
+    Move(Vec3),
+    /// Zoom toward a point of the viewport
+    Zoom {
+        /// Positive zooms in. The radius is multiplied by `e^-amount`.
+        amount: f32,
+        /// Normalised device coordinates in the viewport of the point that stays
+        /// fixed, the origin if [None]
+        focus: Option<Vec2>,
+    },
+    /// Move back to the state the camera was created with
+    Reset,
+    /// Look along an axis, keeping the origin and radius
+    Align(AlignedView),
+    /// Switch between perspective and orthographic projection, keeping the size of
+    /// the focal plane on screen
+    ToggleProjection,
+    /// Switch the controller mode, keeping the transform
+    SetMode(ControllerMode),
+}
+
+/// How the inputs move an [OrbitCamera]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Serialize, Deserialize)]
+#[reflect_value(PartialEq)]
+pub enum ControllerMode {
+    /// Orbits, pans and zooms around the origin. Switching to it moves the origin to
+    /// the mesh at the center of the screen.
+    #[default]
+    Orbit,
+    /// Moves freely along the view direction and turns in place
+    Fly,
+    /// Moves on the horizontal plane and turns in place, with the up vector fixed
+    Walk,
+}
+
+/// Views along the world axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignedView {
+    /// Looking down
+    Top,
+    /// Looking up
+    Bottom,
+    /// Looking toward -Z
+    Front,
+    /// Looking toward +Z
+    Back,
+    /// Looking toward -X
+    Right,
+    /// Looking toward +X
+    Left,
+}
+
+impl AlignedView {
+    /// θ and ϕ of the view. Top and bottom are limited by the ϕ range of the camera.
+    pub fn angles(&self) -> (f32, f32) {
+        match self {
+            // seen from the front, so the back of the scene is up
+            AlignedView::Top => (PI, 0.0),
+            AlignedView::Bottom => (PI, PI),
+            AlignedView::Front => (PI, FRAC_PI_2),
+            AlignedView::Back => (0.0, FRAC_PI_2),
+            AlignedView::Right => (3.0 * FRAC_PI_2, FRAC_PI_2),
+            AlignedView::Left => (FRAC_PI_2, FRAC_PI_2),
+        }
+    }
+}
+
+/// The [OrbitCamera] the inputs go to, kept up to date by the [OrbitCameraPlugin].
+///
+/// [None] until the cursor is over a camera, the inputs then move every camera.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActiveCamera(pub Option<Entity>);
+
+/// What a [FocusCamera] event frames
+#[derive(Debug, Clone, Copy)]
+pub enum FocusTarget {
+    /// The meshes of an entity and its descendants
+    Entity(Entity),
+    /// A box in world space
+    Aabb(Aabb),
+}
+
+/// Moves an enabled [OrbitCamera] so a target fills its viewport.
+///
+/// The origin moves to the center of the target bounds and the radius changes so
+/// the sphere around them fits the field of view.
+#[derive(Debug, Clone, Copy)]
+pub struct FocusCamera {
+    /// Camera to move, [None] moves every enabled camera
+    pub camera: Option<Entity>,
+    pub target: FocusTarget,
+}
+
+/// Position of an [OrbitCamera] around its origin
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+pub struct OrbitState {
+    pub origin: Vec3,
+    pub θ: f32,
+    pub ϕ: f32,
+    pub ρ: f32,
+}
+
+/// Position of the camera relative to the origin, for a unit radius
+fn direction(θ: f32, ϕ: f32) -> Vec3 {
+    let rot = Quat::from_axis_angle(Vec3::Y, θ) * Quat::from_axis_angle(-Vec3::X, ϕ);
+    rot * Vec3::Y
+}
+
+impl OrbitState {
+    /// Position of the camera
+    pub fn eye(&self) -> Vec3 {
+        self.origin + direction(self.θ, self.ϕ) * self.ρ
+    }
+
+    /// The same view around an origin at another distance in front of the camera
+    pub fn with_radius(&self, ρ: f32) -> OrbitState {
+        OrbitState {
+            origin: self.eye() - direction(self.θ, self.ϕ) * ρ,
+            ρ,
+            ..*self
+        }
+    }
+
+    /// Linear interpolation between two states
+    pub fn lerp(&self, other: &OrbitState, t: f32) -> OrbitState {
+        OrbitState {
+            origin: self.origin.lerp(other.origin, t),
+            θ: self.θ + (other.θ - self.θ) * t,
+            ϕ: self.ϕ + (other.ϕ - self.ϕ) * t,
+            ρ: self.ρ + (other.ρ - self.ρ) * t,
+        }
+    }
+}
+
+/// Eased move between two states, see [OrbitCamera::transition_to]
+#[derive(Debug, Clone, Copy)]
+struct Transition {
+    from: OrbitState,
+    to: OrbitState,
+    /// In seconds
+    elapsed: f32,
+    /// In seconds
+    duration: f32,
+}
+
+impl Transition {
+    /// State after some more time, [None] once finished
+    fn advance(&mut self, delta_seconds: f32) -> Option<OrbitState> {
+        self.elapsed += delta_seconds;
+        if self.elapsed >= self.duration {
+            return None;
+        }
+        let t = self.elapsed / self.duration;
+        // smoothstep, starts and ends still
+        Some(self.from.lerp(&self.to, t * t * (3.0 - 2.0 * t)))
+    }
 }
 
-#[derive(Component)]
+#[derive(Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct OrbitCamera {
     origin: Vec3,
     θ: f32, // polar
     ϕ: f32, // azimuthal
     ρ: f32, // radial
+    /// Scale of an orthographic projection
+    scale: f32,
+    target_origin: Vec3,
+    target_θ: f32,
+    target_ϕ: f32,
+    target_ρ: f32,
+    target_scale: f32,
+    /// Orbit speed in radians per second, kept after releasing the input
+    angular_velocity: Vec2,
+    /// Pan speed in units per second, kept after releasing the input
+    pan_velocity: Vec3,
+    /// Rate per second at which the camera approaches its targets, infinite snaps to them
+    smoothing: f32,
+    /// Rate per second at which the momentum decays, infinite stops dead
+    momentum_damping: f32,
+    /// State restored by [CameraAction::Reset]
+    home: OrbitState,
     θ_range: RangeInclusive<f32>,
     ϕ_range: RangeInclusive<f32>,
+    /// Limits the radius, and the scale with an orthographic projection
     ρ_range: RangeInclusive<f32>,
-    rotate_sensitivity: f32, 
-    pan_sensitivity: f32, 
-    zoom_sensitivity: f32, 
-    rotate_button: MouseButton,
-    pan_button: MouseButton,
+    /// θ wraps around instead of being limited to `θ_range`
+    θ_wrap: bool,
+    /// Box the origin is kept in, min and max corners
+    origin_bounds: Option<(Vec3, Vec3)>,
+    /// Closest the camera can get to the bounds of meshes, [None] disables collisions
+    collision_distance: Option<f32>,
+    rotate_sensitivity: f32,
+    pan_sensitivity: f32,
+    zoom_sensitivity: f32,
+    touch_rotate_sensitivity: f32,
+    touch_pan_sensitivity: f32,
+    touch_zoom_sensitivity: f32,
+    /// Speed of the fly and walk modes, in units per second
+    fly_speed: f32,
+    pub(crate) mode: ControllerMode,
     enabled: bool,
+    #[reflect(ignore)]
+    #[serde(skip)]
+    transition: Option<Transition>,
 }
 
 impl Default for OrbitCamera {
     fn default() -> Self {
         OrbitCamera {
             θ: 0.0,
-            ϕ: std::f32::consts::FRAC_PI_2,
+            ϕ: FRAC_PI_2,
             ρ: 5.0,
-            θ_range: 0.01..= std::f32::consts::PI,
-            ϕ_range:0.01..= std::f32::consts::FRAC_PI_2,
-            ρ_range: 0.01..= 1000.,
+            scale: 1.0,
+            θ_range: 0.0..=TAU,
+            ϕ_range: 0.01..=PI - 0.01,
+            ρ_range: 0.01..=1000.,
+            θ_wrap: true,
+            origin_bounds: None,
+            collision_distance: None,
             origin: Vec3::ZERO,
+            target_origin: Vec3::ZERO,
+            target_θ: 0.0,
+            target_ϕ: FRAC_PI_2,
+            target_ρ: 5.0,
+            target_scale: 1.0,
+            angular_velocity: Vec2::ZERO,
+            pan_velocity: Vec3::ZERO,
+            smoothing: 15.0,
+            momentum_damping: 6.0,
+            home: OrbitState {
+                origin: Vec3::ZERO,
+                θ: 0.0,
+                ϕ: FRAC_PI_2,
+                ρ: 5.0,
+            },
             rotate_sensitivity: 1.0,
             pan_sensitivity: 1.0,
             zoom_sensitivity: 0.8,
-            rotate_button: MouseButton::Left,
-            pan_button: MouseButton::Right,
+            touch_rotate_sensitivity: 1.0,
+            touch_pan_sensitivity: 1.0,
+            touch_zoom_sensitivity: 1.0,
+            fly_speed: 4.0,
+            mode: ControllerMode::Orbit,
             enabled: true,
+            transition: None,
         }
     }
 }
@@ -53,42 +311,655 @@ impl OrbitCamera {
         OrbitCamera {
             ρ,
             origin,
+            target_ρ: ρ,
+            target_origin: origin,
+            home: OrbitState {
+                origin,
+                ρ,
+                ..Self::default().home
+            },
             ..Self::default()
         }
     }
+
+    /// Camera at the position of a transform, orbiting around `origin`.
+    ///
+    /// The rotation of the transform is ignored, the camera looks at the origin.
+    /// The state is limited to the default ranges.
+    pub fn from_transform(transform: &Transform, origin: Vec3) -> OrbitCamera {
+        let offset = transform.translation - origin;
+        let ρ = offset.length();
+        if ρ <= 0.0 {
+            return Self::new(Self::default().ρ, origin);
+        }
+        let direction = offset / ρ;
+
+        let mut camera = OrbitCamera::default();
+        camera.jump_to(OrbitState {
+            origin,
+            // inverse of `direction`
+            θ: (-direction.x).atan2(-direction.z).rem_euclid(TAU),
+            ϕ: direction.y.clamp(-1.0, 1.0).acos(),
+            ρ,
+        });
+        camera.home = camera.state();
+        camera
+    }
+
+    /// Current state
+    pub fn state(&self) -> OrbitState {
+        OrbitState {
+            origin: self.origin,
+            θ: self.θ,
+            ϕ: self.ϕ,
+            ρ: self.ρ,
+        }
+    }
+
+    /// Sets the state the camera moves toward
+    ///
+    /// If θ wraps, the camera takes the short way around.
+    pub fn set_target(&mut self, target: OrbitState) {
+        self.target_origin = target.origin;
+        self.target_θ = target.θ;
+        self.target_ϕ = target.ϕ;
+        self.target_ρ = target.ρ;
+        self.angular_velocity = Vec2::ZERO;
+        self.pan_velocity = Vec3::ZERO;
+        self.transition = None;
+        self.constrain_targets();
+        if self.θ_wrap {
+            self.target_θ = self.θ + (self.target_θ - self.θ + PI).rem_euclid(TAU) - PI;
+        }
+    }
+
+    /// Moves the camera to a state in `duration` seconds, easing in and out.
+    ///
+    /// Input interrupts the transition.
+    pub fn transition_to(&mut self, target: OrbitState, duration: f32) {
+        self.set_target(target);
+        let from = self.state();
+        if duration > 0.0 {
+            self.transition = Some(Transition {
+                from,
+                to: OrbitState {
+                    origin: self.target_origin,
+                    θ: self.target_θ,
+                    ϕ: self.target_ϕ,
+                    ρ: self.target_ρ,
+                },
+                elapsed: 0.0,
+                duration,
+            });
+        }
+    }
+
+    /// Limits the angles and radius to ranges. θ is only limited if it does not wrap.
+    ///
+    /// # Panics
+    /// If the ranges are out of order
+    pub fn with_ranges(
+        mut self,
+        θ: Option<RangeInclusive<f32>>,
+        ϕ: RangeInclusive<f32>,
+        ρ: RangeInclusive<f32>,
+    ) -> Self {
+        assert!(ϕ.start() <= ϕ.end() && ρ.start() <= ρ.end());
+        self.θ_wrap = θ.is_none();
+        if let Some(θ) = θ {
+            assert!(θ.start() <= θ.end());
+            self.θ_range = θ;
+        }
+        self.ϕ_range = ϕ;
+        self.ρ_range = ρ;
+        self.constrain_targets();
+        self
+    }
+
+    /// Keeps the origin inside a box, given by its min and max corners
+    pub fn with_origin_bounds(mut self, min: Vec3, max: Vec3) -> Self {
+        self.origin_bounds = Some((min.min(max), min.max(max)));
+        self.constrain_targets();
+        self
+    }
+
+    /// Keeps the camera at least at `distance` from the [Aabb]s of meshes.
+    ///
+    /// Meshes whose bounds contain the origin are ignored, so the camera can orbit
+    /// inside them.
+    pub fn with_collision_distance(mut self, distance: f32) -> Self {
+        self.collision_distance = Some(distance);
+        self
+    }
+
+    /// Applies the ranges and bounds to the targets
+    ///
+    /// The radius range and origin bounds only apply in [ControllerMode::Orbit],
+    /// the fly and walk modes turn around a point in front of the eye.
+    fn constrain_targets(&mut self) {
+        if self.θ_wrap {
+            let wrapped = self.target_θ.rem_euclid(TAU);
+            // shift the current angle the same, so it does not spin back
+            self.θ += wrapped - self.target_θ;
+            self.target_θ = wrapped;
+        } else {
+            self.target_θ = clamp_range(self.target_θ, &self.θ_range);
+        }
+        self.target_ϕ = clamp_range(self.target_ϕ, &self.ϕ_range);
+        self.target_scale = clamp_range(self.target_scale, &self.ρ_range);
+        if self.mode != ControllerMode::Orbit {
+            return;
+        }
+        self.target_ρ = clamp_range(self.target_ρ, &self.ρ_range);
+        if let Some((min, max)) = self.origin_bounds {
+            self.target_origin = self.target_origin.clamp(min, max);
+        }
+    }
+
+    /// Position of the camera relative to the origin, for a unit radius
+    fn direction(&self) -> Vec3 {
+        direction(self.θ, self.ϕ)
+    }
+
+    /// Moves the camera to a state without transition
+    fn jump_to(&mut self, state: OrbitState) {
+        self.set_target(state);
+        self.origin = self.target_origin;
+        self.θ = self.target_θ;
+        self.ϕ = self.target_ϕ;
+        self.ρ = self.target_ρ;
+    }
+
+    /// Sets the movement speed of the fly and walk modes, in units per second.
+    ///
+    /// Holding shift moves faster and holding control slower.
+    pub fn with_fly_speed(mut self, speed: f32) -> Self {
+        self.fly_speed = speed;
+        self
+    }
+
+    /// Current controller mode
+    pub fn mode(&self) -> ControllerMode {
+        self.mode
+    }
+
+    /// Sets the exponential damping of the motion.
+    ///
+    /// `smoothing` is the rate per second at which the camera approaches where the
+    /// input moves it and `momentum_damping` the rate at which it stops after the
+    /// input is released. [f32::INFINITY] disables either of them.
+    pub fn with_damping(mut self, smoothing: f32, momentum_damping: f32) -> Self {
+        self.smoothing = smoothing;
+        self.momentum_damping = momentum_damping;
+        self
+    }
+
+    /// Stops a transition where the camera currently is
+    fn interrupt_transition(&mut self) {
+        if self.transition.take().is_some() {
+            self.target_origin = self.origin;
+            self.target_θ = self.θ;
+            self.target_ϕ = self.ϕ;
+            self.target_ρ = self.ρ;
+        }
+    }
+
+    /// Half the size of the view in world units on the focal plane, once the
+    /// camera reaches its targets
+    fn focal_half_extents(&self, projection: &Projection) -> Vec2 {
+        match projection {
+            Projection::Perspective(perspective) => {
+                let half_height = self.target_ρ * (perspective.fov * 0.5).tan();
+                Vec2::new(half_height * perspective.aspect_ratio, half_height)
+            }
+            Projection::Orthographic(orthographic) => {
+                Vec2::new(
+                    orthographic.right - orthographic.left,
+                    orthographic.top - orthographic.bottom,
+                ) * 0.5
+                    * self.target_scale
+            }
+        }
+    }
+
+    /// World units per logical pixel on the focal plane of a viewport
+    fn pixel_size(&self, projection: &Projection, viewport_size: Option<Vec2>) -> Vec2 {
+        match viewport_size {
+            Some(size) if size.cmpgt(Vec2::ZERO).all() => {
+                self.focal_half_extents(projection) * 2.0 / size
+            }
+            _ => Vec2::ZERO,
+        }
+    }
+
+    /// Scales the radius, or the scale of an orthographic projection, by `e^-amount`.
+    ///
+    /// With a focus in NDC the origin moves so the point of the focal plane under the
+    /// focus stays in place, the view having the `rotation`.
+    fn zoom(&mut self, amount: f32, focus: Option<Vec2>, rotation: Quat, projection: &Projection) {
+        if self.transition.is_some() {
+            self.interrupt_transition();
+        }
+        let half_extents = self.focal_half_extents(projection);
+        // orthographic views do not get smaller with distance
+        let scale = match projection {
+            Projection::Perspective(_) => {
+                let ρ = self.target_ρ;
+                self.target_ρ *= (-amount).exp();
+                self.constrain_targets();
+                self.target_ρ / ρ
+            }
+            Projection::Orthographic(_) => {
+                let scale = self.target_scale;
+                self.target_scale *= (-amount).exp();
+                self.constrain_targets();
+                self.target_scale / scale
+            }
+        };
+
+        if let Some(focus) = focus {
+            // scaling the view around a point of the focal plane keeps it at the
+            // same place on screen
+            let focus = self.target_origin
+                + rotation * Vec3::X * focus.x * half_extents.x
+                + rotation * Vec3::Y * focus.y * half_extents.y;
+            self.target_origin = focus + (self.target_origin - focus) * scale;
+            self.constrain_targets();
+        }
+    }
+
+    /// The camera has reached its targets
+    fn is_settled(&self) -> bool {
+        self.transition.is_none()
+            && self.θ == self.target_θ
+            && self.ϕ == self.target_ϕ
+            && self.ρ == self.target_ρ
+            && self.scale == self.target_scale
+            && self.origin == self.target_origin
+    }
+
+    /// Moves the camera toward its targets, independent of the frame rate.
+    fn approach_targets(&mut self, delta_seconds: f32) {
+        if let Some(transition) = &mut self.transition {
+            match transition.advance(delta_seconds) {
+                Some(state) => {
+                    self.origin = state.origin;
+                    self.θ = state.θ;
+                    self.ϕ = state.ϕ;
+                    self.ρ = state.ρ;
+                    return;
+                }
+                None => self.transition = None,
+            }
+        }
+        let t = if self.smoothing.is_finite() {
+            1.0 - (-self.smoothing * delta_seconds).exp()
+        } else {
+            1.0
+        };
+        self.θ = approach(self.θ, self.target_θ, t);
+        self.ϕ = approach(self.ϕ, self.target_ϕ, t);
+        self.ρ = approach(self.ρ, self.target_ρ, t);
+        self.scale = approach(self.scale, self.target_scale, t);
+        self.origin = self.origin.lerp(self.target_origin, t);
+        if self.origin.distance(self.target_origin) < SETTLE_EPSILON {
+            self.origin = self.target_origin;
+        }
+    }
+
+    /// Sets the sensitivities of the touch controls, independent of the mouse ones.
+    ///
+    /// `zoom` scales the pinch, with 1.0 the pinched distance follows the fingers.
+    pub fn with_touch_sensitivity(mut self, rotate: f32, pan: f32, zoom: f32) -> Self {
+        self.touch_rotate_sensitivity = rotate;
+        self.touch_pan_sensitivity = pan;
+        self.touch_zoom_sensitivity = zoom;
+        self
+    }
+}
+
+/// The camera with the highest priority among those a point is in the viewport of
+fn hovered_camera<'a>(
+    cameras: impl Iterator<Item = (Entity, &'a Camera)>,
+    to_ndc: impl Fn(&Camera) -> Option<Vec2>,
+) -> Option<Entity> {
+    cameras
+        .filter(|(_, camera)| camera.is_active && to_ndc(camera).is_some())
+        .max_by_key(|(_, camera)| camera.priority)
+        .map(|(entity, _)| entity)
+}
+
+/// The camera that keeps the inputs after the `active` one, given the `hovered`
+/// one.
+///
+/// A drag stays with the camera it started on, and the last active camera keeps the
+/// inputs while none is hovered.
+fn active_camera(
+    active: Option<Entity>,
+    hovered: Option<Entity>,
+    dragging: bool,
+) -> Option<Entity> {
+    match active {
+        Some(_) if dragging => active,
+        _ => hovered.or(active),
+    }
+}
+
+/// Normalised device coordinates of the cursor in the viewport of a camera
+/// rendering to a window, [None] if it is outside of it.
+fn cursor_ndc(camera: &Camera, windows: &Windows) -> Option<Vec2> {
+    let window = match camera.target {
+        RenderTarget::Window(id) => windows.get(id)?,
+        _ => return None,
+    };
+    viewport_ndc(camera, window, window.cursor_position()?)
+}
+
+fn clamp_range(value: f32, range: &RangeInclusive<f32>) -> f32 {
+    value.max(*range.start()).min(*range.end())
+}
+
+/// World space box around the bounds of a mesh, min and max corners.
+fn world_bounds(aabb: &Aabb, matrix: &Mat4) -> (Vec3, Vec3) {
+    let center = Vec3::from(aabb.center);
+    let half_extents = Vec3::from(aabb.half_extents);
+    (0..8)
+        .map(|i| {
+            let corner = Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            matrix.transform_point3(center + half_extents * corner)
+        })
+        .fold(
+            (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+            |(min, max), corner| (min.min(corner), max.max(corner)),
+        )
+}
+
+/// World space bounds of the meshes of an entity and its descendants, min and max
+/// corners. [None] if none of them have an [Aabb].
+fn entity_bounds(
+    entity: Entity,
+    meshes: &Query<(&Aabb, &GlobalTransform)>,
+    children: &Query<&Children>,
+) -> Option<(Vec3, Vec3)> {
+    let own = meshes
+        .get(entity)
+        .ok()
+        .map(|(aabb, transform)| world_bounds(aabb, &transform.compute_matrix()));
+    children
+        .get(entity)
+        .into_iter()
+        .flat_map(|entity_children| entity_children.iter())
+        .filter_map(|child| entity_bounds(*child, meshes, children))
+        .chain(own)
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+}
+
+/// Distance from the center of a sphere at which it fills the field of view,
+/// [None] for projections that do not get smaller with distance.
+fn fit_distance(projection: &Projection, radius: f32) -> Option<f32> {
+    match projection {
+        Projection::Perspective(perspective) => {
+            let half_fov_tan = (perspective.fov * 0.5).tan();
+            // the narrower of the vertical and horizontal fields of view
+            let half_fov = half_fov_tan
+                .min(half_fov_tan * perspective.aspect_ratio)
+                .atan();
+            Some(radius / half_fov.sin())
+        }
+        Projection::Orthographic(_) => None,
+    }
+}
+
+/// Distance along a ray to where it enters a box, [None] if it misses it or
+/// starts inside.
+fn ray_box_entry(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    if origin.cmpge(min).all() && origin.cmple(max).all() {
+        return None;
+    }
+    let (mut t_enter, mut t_exit) = (0.0f32, f32::INFINITY);
+    for axis in 0..3 {
+        if direction[axis] == 0.0 {
+            // parallel to the slab, the ray is always or never between its planes
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t_1 = (min[axis] - origin[axis]) / direction[axis];
+        let t_2 = (max[axis] - origin[axis]) / direction[axis];
+        t_enter = t_enter.max(t_1.min(t_2));
+        t_exit = t_exit.min(t_1.max(t_2));
+    }
+    (t_enter <= t_exit).then_some(t_enter)
+}
+
+/// Interpolates a value toward a target, snapping to it once close enough.
+fn approach(current: f32, target: f32, t: f32) -> f32 {
+    let next = current + (target - current) * t;
+    if (target - next).abs() < SETTLE_EPSILON {
+        target
+    } else {
+        next
+    }
+}
+
+/// Factor a velocity keeps after some time with exponential damping
+fn damping_factor(damping: f32, delta_seconds: f32) -> f32 {
+    if damping.is_finite() {
+        (-damping * delta_seconds).exp()
+    } else {
+        0.0
+    }
+}
+
+/// Offset of the origin for a pan of `delta` pixels, y down, so the view follows
+/// the pointer.
+fn pan_offset(delta: Vec2, pixel_size: Vec2, rotation: Quat) -> Vec3 {
+    let delta = delta * pixel_size;
+    rotation * Vec3::new(-delta.x, delta.y, 0.0)
+}
+
+/// Moves the targets of a camera by the orbit delta in pixels and the pan offset in
+/// world units of a frame, or by the momentum left once they are released.
+///
+/// Takes the camera through [DerefMut] so still cameras are not marked as changed.
+fn apply_motion(
+    camera: &mut impl DerefMut<Target = OrbitCamera>,
+    orbit: Option<Vec2>,
+    pan: Option<Vec3>,
+    delta_seconds: f32,
+) {
+    if (orbit.is_some() || pan.is_some()) && camera.transition.is_some() {
+        camera.interrupt_transition();
+    }
+
+    // while the input is held it sets the velocity, once released it decays
+    let decay = damping_factor(camera.momentum_damping, delta_seconds);
+    let mut angular_velocity = match orbit {
+        Some(delta) if delta_seconds > 0.0 => -delta * RADIANS_PER_PIXEL / delta_seconds,
+        Some(_) => Vec2::ZERO,
+        None => camera.angular_velocity * decay,
+    };
+    let mut pan_velocity = match pan {
+        // the origin moves by the whole offset this frame
+        Some(offset) if delta_seconds > 0.0 => offset / delta_seconds,
+        Some(_) => Vec3::ZERO,
+        None => camera.pan_velocity * decay,
+    };
+    if angular_velocity.length() < SETTLE_EPSILON {
+        angular_velocity = Vec2::ZERO;
+    }
+    if pan_velocity.length() < SETTLE_EPSILON {
+        pan_velocity = Vec3::ZERO;
+    }
+    // avoid marking still cameras as changed
+    if camera.angular_velocity != angular_velocity {
+        camera.angular_velocity = angular_velocity;
+    }
+    if camera.pan_velocity != pan_velocity {
+        camera.pan_velocity = pan_velocity;
+    }
+
+    if camera.angular_velocity != Vec2::ZERO {
+        let angular_delta = camera.angular_velocity * delta_seconds;
+        camera.target_θ += angular_delta.x;
+        camera.target_ϕ += angular_delta.y;
+        camera.constrain_targets();
+    }
+    if camera.pan_velocity != Vec3::ZERO {
+        let pan_delta = camera.pan_velocity * delta_seconds;
+        camera.target_origin += pan_delta;
+        camera.constrain_targets();
+    }
 }
 
 pub struct OrbitCameraPlugin;
 
 impl OrbitCameraPlugin {
-    fn update_transform_system( mut query: Query<(&OrbitCamera, &mut Transform), (With<Camera>, Changed<OrbitCamera>)>) -> (){
-        for (orbit_camera, mut transform) in query.iter_mut() {
-            if orbit_camera.enabled {
-                let rot = Quat::from_axis_angle(Vec3::Y, orbit_camera.θ)
-                    * Quat::from_axis_angle(-Vec3::X, orbit_camera.ϕ);
-                transform.translation = (rot * Vec3::Y) * orbit_camera.ρ + orbit_camera.origin;
+    fn update_transform_system(
+        time: Res<Time>,
+        mut query: Query<(&mut OrbitCamera, &mut Transform, &mut Projection), With<Camera>>,
+    ) -> () {
+        for (mut orbit_camera, mut transform, mut projection) in query.iter_mut() {
+            if !orbit_camera.is_settled() {
+                orbit_camera.approach_targets(time.delta_seconds());
+            }
+            if orbit_camera.enabled && orbit_camera.is_changed() {
+                transform.translation =
+                    orbit_camera.direction() * orbit_camera.ρ + orbit_camera.origin;
                 transform.look_at(orbit_camera.origin, Vec3::Y);
+                if let Projection::Orthographic(orthographic) = projection.as_mut() {
+                    orthographic.scale = orbit_camera.scale;
+                }
             }
         }
     }
-    pub fn emit_motion_events(
-        mut events: EventWriter<CameraEvents>,
-        mut mouse_motion_events: EventReader<MouseMotion>,
-        mouse_button_input: Res<Input<MouseButton>>,
-        mut query: Query<&OrbitCamera>,
+    /// Sends the camera events of the inputs bound with the [CameraBindings] of the
+    /// active camera.
+    ///
+    /// The [ActiveCamera] is the one whose viewport contains the cursor, with the
+    /// highest priority if they overlap. A drag stays with the camera it started on,
+    /// and the last active camera keeps the inputs while the cursor is outside of the
+    /// windows. Without any, the inputs move every camera.
+    pub fn emit_input_events(
+        time: Res<Time>,
+        mut events: EventWriter<CameraEvent>,
+        mut inputs: CameraInputs,
+        windows: Option<Res<Windows>>,
+        mut active: ResMut<ActiveCamera>,
+        query: Query<(Entity, &OrbitCamera, &Camera, Option<&CameraBindings>)>,
     ) {
-        let mut mouse_position_delta = Vec2::ZERO;
-        for event in mouse_motion_events.iter() {
-            mouse_position_delta += event.delta;
+        let inputs = inputs.frame(time.delta_seconds());
+        let default_bindings = CameraBindings::default();
+
+        let hovered = windows.as_deref().and_then(|windows| {
+            hovered_camera(
+                query
+                    .iter()
+                    .filter(|(_, orbit_camera, ..)| orbit_camera.enabled)
+                    .map(|(entity, _, camera, _)| (entity, camera)),
+                |camera| cursor_ndc(camera, windows),
+            )
+        });
+        let next = active_camera(active.0, hovered, inputs.dragging());
+        // avoid marking the resource as changed every frame
+        if active.0 != next {
+            active.0 = next;
         }
-        for orbit_camera in query.iter_mut() {
-            if orbit_camera.enabled {
-                if mouse_button_input.pressed(orbit_camera.rotate_button) {
-                    events.send(CameraEvents::Orbit(mouse_position_delta))
+        let target = active.0.filter(|entity| {
+            query
+                .get(*entity)
+                .map_or(false, |(_, orbit_camera, ..)| orbit_camera.enabled)
+        });
+
+        for (entity, orbit_camera, camera, bindings) in query.iter() {
+            if !orbit_camera.enabled || target.map_or(false, |target| target != entity) {
+                continue;
+            }
+            let mut send = |action| {
+                events.send(CameraEvent {
+                    camera: entity,
+                    action,
+                })
+            };
+            let bindings = bindings.unwrap_or(&default_bindings);
+
+            if orbit_camera.mode == ControllerMode::Orbit {
+                let orbit = inputs.read(&bindings.orbit, bindings);
+                if !orbit.is_empty() {
+                    let delta = orbit
+                        .iter()
+                        .fold(Vec2::ZERO, |delta, value| delta + value.motion());
+                    send(CameraAction::Orbit(delta * orbit_camera.rotate_sensitivity));
+                }
+
+                let pan = inputs.read(&bindings.pan, bindings);
+                if !pan.is_empty() {
+                    let delta = pan
+                        .iter()
+                        .fold(Vec2::ZERO, |delta, value| delta + value.motion());
+                    send(CameraAction::Pan(delta * orbit_camera.pan_sensitivity));
+                }
+
+                let zoom = inputs.read(&bindings.zoom, bindings);
+                let lines: f32 = zoom.iter().map(|value| value.lines()).sum();
+                if lines != 0.0 {
+                    // the mouse zooms toward the cursor, keys and gamepads toward the origin
+                    let focus = zoom
+                        .iter()
+                        .any(|value| !matches!(value, AxisValue::Scaled(_)))
+                        .then(|| {
+                            windows
+                                .as_deref()
+                                .and_then(|windows| cursor_ndc(camera, windows))
+                        })
+                        .flatten();
+                    // every line multiplies the radius by the sensitivity
+                    send(CameraAction::Zoom {
+                        amount: -lines * orbit_camera.zoom_sensitivity.ln(),
+                        focus,
+                    });
+                }
+            } else {
+                let look = inputs.read(&bindings.look, bindings);
+                if !look.is_empty() {
+                    let delta = look
+                        .iter()
+                        .fold(Vec2::ZERO, |delta, value| delta + value.motion());
+                    send(CameraAction::Orbit(delta * orbit_camera.rotate_sensitivity));
                 }
 
-                if mouse_button_input.pressed(orbit_camera.pan_button) {
-                    events.send(CameraEvents::Pan(mouse_position_delta))
+                let movement = inputs.direction(&bindings.movement).clamp_length_max(1.0);
+                let vertical = inputs.direction(&bindings.vertical).y.clamp(-1.0, 1.0);
+                if movement != Vec2::ZERO || vertical != 0.0 {
+                    let modifiers = inputs.modifiers();
+                    let speed = orbit_camera.fly_speed
+                        * if modifiers.shift { FAST_FACTOR } else { 1.0 }
+                        * if modifiers.ctrl { SLOW_FACTOR } else { 1.0 };
+                    // screen directions are y down, so down moves back
+                    let offset = Vec3::new(movement.x, vertical, movement.y);
+                    send(CameraAction::Move(offset * speed * time.delta_seconds()));
+                }
+            }
+
+            if inputs.just_pressed(&bindings.reset) {
+                send(CameraAction::Reset);
+            }
+            if inputs.just_pressed(&bindings.toggle_projection) {
+                send(CameraAction::ToggleProjection);
+            }
+            for (binding, view) in bindings.aligned_views.iter() {
+                if inputs.just_pressed(std::slice::from_ref(binding)) {
+                    send(CameraAction::Align(*view));
+                }
+            }
+            for (binding, mode) in bindings.modes.iter() {
+                if inputs.just_pressed(std::slice::from_ref(binding)) {
+                    send(CameraAction::SetMode(*mode));
                 }
             }
         }
@@ -96,71 +967,349 @@ impl OrbitCameraPlugin {
 
     pub fn mouse_motion_system(
         time: Res<Time>,
-        mut events: EventReader<CameraEvents>,
-        mut query: Query<(&mut OrbitCamera, &mut Transform, &mut Camera)>,
+        mut events: EventReader<CameraEvent>,
+        mut query: Query<(Entity, &mut OrbitCamera, &Transform, &Camera, &Projection)>,
     ) {
-        for (mut camera, transform, _) in query.iter_mut() {
+        let delta_seconds = time.delta_seconds();
+        let events: Vec<_> = events.iter().collect();
+        for (entity, mut camera, transform, render_camera, projection) in query.iter_mut() {
             if !camera.enabled {
                 continue;
             }
 
-            for event in events.iter() {
-                match event {
-                    CameraEvents::Orbit(delta) => {
-                        camera.θ -= delta.x * camera.rotate_sensitivity * time.delta_seconds();
-                        camera.ϕ -= delta.y * camera.rotate_sensitivity * time.delta_seconds();
-                        camera.ϕ = camera
-                            .ϕ
-                            .max(*camera.ϕ_range.start())
-                            .min(*camera.ϕ_range.end());
+            let pixel_size = camera.pixel_size(projection, render_camera.logical_viewport_size());
+
+            let mut orbit = None;
+            let mut pan = None;
+            let mut reset = false;
+            let mut align = None;
+            for event in events.iter().filter(|event| event.camera == entity) {
+                match &event.action {
+                    CameraAction::Reset => reset = true,
+                    CameraAction::Align(view) => align = Some(*view),
+                    CameraAction::Orbit(delta) => {
+                        *orbit.get_or_insert(Vec2::ZERO) += *delta;
+                    }
+                    CameraAction::Pan(delta) => {
+                        *pan.get_or_insert(Vec3::ZERO) +=
+                            pan_offset(*delta, pixel_size, transform.rotation);
                     }
-                    CameraEvents::Pan(delta) => {
-                        let right_dir = transform.rotation * -Vec3::X;
-                        let up_dir = transform.rotation * Vec3::Y;
-                        let pan_vector = (delta.x * right_dir + delta.y * up_dir)
-                            * camera.pan_sensitivity
-                            * time.delta_seconds();
-                        camera.origin += pan_vector;
+                    CameraAction::Move(offset) => {
+                        let offset = match camera.mode {
+                            ControllerMode::Walk => {
+                                let horizontal = Vec3::new(1.0, 0.0, 1.0);
+                                let right =
+                                    (transform.rotation * Vec3::X * horizontal).normalize_or_zero();
+                                let back =
+                                    (transform.rotation * Vec3::Z * horizontal).normalize_or_zero();
+                                offset.x * right + offset.z * back
+                            }
+                            _ => transform.rotation * *offset,
+                        };
+                        *pan.get_or_insert(Vec3::ZERO) += offset;
                     }
                     _ => {}
                 }
             }
+
+            apply_motion(&mut camera, orbit, pan, delta_seconds);
+
+            if reset {
+                let home = match camera.mode {
+                    ControllerMode::Orbit => camera.home,
+                    _ => camera.home.with_radius(FLY_RADIUS),
+                };
+                camera.set_target(home);
+            }
+            if let Some(view) = align {
+                let (θ, ϕ) = view.angles();
+                let target = OrbitState {
+                    origin: camera.target_origin,
+                    θ,
+                    ϕ,
+                    ρ: camera.target_ρ,
+                };
+                camera.set_target(target);
+            }
         }
     }
-    pub fn emit_zoom_events(
-        mut events: EventWriter<CameraEvents>,
-        mut mouse_wheel_events: EventReader<MouseWheel>,
-        mut query: Query<&OrbitCamera>,
+
+    /// Sends orbit events when dragging one finger, and pan and zoom events from the
+    /// [Gesture::TwoFingerPan] and [Gesture::Pinch] of the
+    /// [GesturePlugin](crate::events::gestures::GesturePlugin).
+    ///
+    /// The events go to the camera whose viewport contains the first finger, or to
+    /// every camera without a window.
+    pub fn emit_touch_events(
+        mut events: EventWriter<CameraEvent>,
+        touches: Option<Res<Touches>>,
+        gestures: Option<Res<Events<Gesture>>>,
+        mut gesture_reader: Local<ManualEventReader<Gesture>>,
+        mut last_position: Local<Option<(u64, Vec2)>>,
+        windows: Option<Res<Windows>>,
+        query: Query<(Entity, &OrbitCamera, &Camera)>,
     ) {
-        let mut total = 0.0;
-        for event in mouse_wheel_events.iter() {
-            total += event.y
-                * match event.unit {
-                    Line => 1.0,
-                    Pixel => LINE_TO_PIXEL_RATIO,
-                };
-        }
+        let touches = match touches {
+            Some(touches) => touches,
+            None => return,
+        };
+        let gestures: Vec<Gesture> = match gestures {
+            Some(gestures) => gesture_reader.iter(&gestures).copied().collect(),
+            None => Vec::new(),
+        };
+
+        let first = touches
+            .iter()
+            .min_by_key(|touch| touch.id())
+            .map(|touch| (touch.id(), touch.position()));
+        // touches are y up, camera events y down like the mouse
+        let flip = |delta: Vec2| Vec2::new(delta.x, -delta.y);
+        // the touch delta is since its last move, not since the last frame
+        let orbit = match (touches.iter().count(), first, *last_position) {
+            (1, Some((id, position)), Some((last_id, last))) if id == last_id => {
+                Some(flip(position - last))
+            }
+            (1, Some(_), _) => Some(Vec2::ZERO),
+            _ => None,
+        };
+        *last_position = first;
 
-        if total != 0.0 {
-            for camera in query.iter_mut() {
-                if camera.enabled {
-                    events.send(CameraEvents::Zoom(total));
+        let first_position = match first {
+            Some((_, position)) => position,
+            None => return,
+        };
+        // touch events carry no window, they are on the primary one
+        let window = windows.as_ref().and_then(|windows| windows.get_primary());
+        let target = window.and_then(|window| {
+            hovered_camera(
+                query
+                    .iter()
+                    .filter(|(_, camera, _)| camera.enabled)
+                    .map(|(entity, _, render_camera)| (entity, render_camera)),
+                |render_camera| viewport_ndc(render_camera, window, first_position),
+            )
+        });
+        for (entity, camera, render_camera) in query.iter() {
+            if !camera.enabled || (window.is_some() && target != Some(entity)) {
+                continue;
+            }
+            let mut send = |action| {
+                events.send(CameraEvent {
+                    camera: entity,
+                    action,
+                })
+            };
+            // sent while the finger is down, even still, so momentum starts on release
+            if let Some(delta) = orbit {
+                send(CameraAction::Orbit(delta * camera.touch_rotate_sensitivity));
+            }
+            for gesture in &gestures {
+                match *gesture {
+                    Gesture::TwoFingerPan { delta } => {
+                        send(CameraAction::Pan(
+                            flip(delta) * camera.touch_pan_sensitivity,
+                        ));
+                    }
+                    // fingers moving apart zoom in, toward the point between them
+                    Gesture::Pinch { scale, center } => send(CameraAction::Zoom {
+                        amount: scale.ln() * camera.touch_zoom_sensitivity,
+                        focus: window
+                            .and_then(|window| viewport_ndc(render_camera, window, center)),
+                    }),
+                    _ => {}
                 }
             }
         }
     }
 
+    /// Scales the radius and moves the origin so the focus point of the zoom
+    /// stays under the cursor.
     pub fn zoom_system(
-        mut query: Query<&mut OrbitCamera, With<Camera>>,
-        mut events: EventReader<CameraEvents>,
+        mut query: Query<(Entity, &mut OrbitCamera, &Transform, &Projection), With<Camera>>,
+        mut events: EventReader<CameraEvent>,
+    ) {
+        let events: Vec<_> = events.iter().collect();
+        for (entity, mut camera, transform, projection) in query.iter_mut() {
+            if !camera.enabled || camera.mode != ControllerMode::Orbit {
+                continue;
+            }
+            for event in events.iter().filter(|event| event.camera == entity) {
+                if let CameraAction::Zoom { amount, focus } = event.action {
+                    camera.zoom(amount, focus, transform.rotation, projection);
+                }
+            }
+        }
+    }
+
+    /// Switches the projection of the cameras on [CameraAction::ToggleProjection].
+    ///
+    /// The orthographic view is as high as the perspective one at the origin.
+    /// Switching back restores the field of view.
+    pub fn projection_system(
+        mut events: EventReader<CameraEvent>,
+        mut query: Query<(Entity, &mut OrbitCamera, &mut Projection)>,
+        mut perspectives: Local<HashMap<Entity, PerspectiveProjection>>,
+    ) {
+        for event in events.iter() {
+            let (entity, mut camera, mut projection) = match query.get_mut(event.camera) {
+                Ok(camera) if event.action == CameraAction::ToggleProjection => camera,
+                _ => continue,
+            };
+            if !camera.enabled {
+                continue;
+            }
+            let toggled = match projection.as_ref() {
+                Projection::Perspective(perspective) => {
+                    // the scaling mode makes the scale the half height of the view
+                    let half_height = camera.ρ * (perspective.fov * 0.5).tan();
+                    camera.scale = half_height;
+                    camera.target_scale = half_height;
+                    camera.constrain_targets();
+                    perspectives.insert(entity, perspective.clone());
+                    Projection::Orthographic(OrthographicProjection {
+                        scale: camera.scale,
+                        scaling_mode: ScalingMode::FixedVertical(2.0),
+                        ..default()
+                    })
+                }
+                Projection::Orthographic(orthographic) => {
+                    let perspective = perspectives.remove(&entity).unwrap_or_default();
+                    let half_height =
+                        (orthographic.top - orthographic.bottom) * 0.5 * orthographic.scale;
+                    let ρ = half_height / (perspective.fov * 0.5).tan();
+                    camera.ρ = ρ;
+                    camera.target_ρ = ρ;
+                    camera.constrain_targets();
+                    Projection::Perspective(perspective)
+                }
+            };
+            *projection = toggled;
+        }
+    }
+
+    /// Switches the controller mode on [CameraAction::SetMode], keeping the transform.
+    ///
+    /// The fly and walk modes turn around a point just in front of the camera.
+    /// Switching to orbit casts a ray from the center of the screen and orbits
+    /// around the nearest mesh it hits, or at the home radius if it hits none.
+    pub fn mode_system(
+        mut events: EventReader<CameraEvent>,
+        mut cameras: Query<&mut OrbitCamera>,
+        meshes: Res<Assets<Mesh>>,
+        mesh_query: Query<(&Handle<Mesh>, &GlobalTransform, Option<&Aabb>)>,
     ) {
-        for mut camera in query.iter_mut() {
-            for event in events.iter() {
-                if camera.enabled {
-                    if let CameraEvents::Zoom(distance) = event {
-                        camera.ρ *= camera.zoom_sensitivity.powf(*distance);
+        for event in events.iter() {
+            let (mut camera, mode) = match (cameras.get_mut(event.camera), event.action) {
+                (Ok(camera), CameraAction::SetMode(mode)) => (camera, mode),
+                _ => continue,
+            };
+            if !camera.enabled || mode == camera.mode {
+                continue;
+            }
+
+            let state = camera.state();
+            let ρ = match mode {
+                ControllerMode::Orbit => {
+                    let ray = Ray {
+                        origin: state.eye(),
+                        direction: -camera.direction(),
+                    };
+                    mesh_query
+                        .iter()
+                        .filter_map(|(mesh, transform, aabb)| {
+                            ray_mesh_intersection(
+                                &ray,
+                                meshes.get(mesh)?,
+                                &transform.compute_matrix(),
+                                aabb,
+                            )
+                        })
+                        .map(|hit| hit.distance)
+                        .reduce(f32::min)
+                        .unwrap_or(camera.home.ρ)
+                }
+                ControllerMode::Fly | ControllerMode::Walk => FLY_RADIUS,
+            };
+            camera.mode = mode;
+            camera.jump_to(state.with_radius(ρ));
+        }
+    }
+
+    /// Moves the cameras to frame the targets of [FocusCamera] events.
+    pub fn focus_system(
+        mut events: EventReader<FocusCamera>,
+        mut cameras: Query<(Entity, &mut OrbitCamera, &Projection)>,
+        meshes: Query<(&Aabb, &GlobalTransform)>,
+        children: Query<&Children>,
+    ) {
+        for event in events.iter() {
+            let (min, max) = match event.target {
+                FocusTarget::Entity(entity) => match entity_bounds(entity, &meshes, &children) {
+                    Some(bounds) => bounds,
+                    None => {
+                        warn!("Cannot focus on {:?}, it has no mesh bounds", entity);
+                        continue;
                     }
+                },
+                FocusTarget::Aabb(aabb) => (aabb.min().into(), aabb.max().into()),
+            };
+            let center = (min + max) * 0.5;
+            let radius = (max - min).length() * 0.5;
+
+            for (entity, mut camera, projection) in cameras.iter_mut() {
+                if !camera.enabled || event.camera.map_or(false, |target| target != entity) {
+                    continue;
                 }
+                let mut target = OrbitState {
+                    origin: center,
+                    θ: camera.target_θ,
+                    ϕ: camera.target_ϕ,
+                    ρ: camera.target_ρ,
+                };
+                if let Some(distance) = fit_distance(projection, radius) {
+                    target.ρ = distance;
+                }
+                // framing orbits around the target
+                camera.mode = ControllerMode::Orbit;
+                camera.set_target(target);
+                if let Projection::Orthographic(orthographic) = projection {
+                    let half_size = Vec2::new(
+                        orthographic.right - orthographic.left,
+                        orthographic.top - orthographic.bottom,
+                    ) * 0.5;
+                    camera.target_scale = radius / half_size.min_element();
+                    camera.constrain_targets();
+                }
+            }
+        }
+    }
+
+    /// Shortens the radius of cameras that would be closer to a mesh than their
+    /// collision distance.
+    pub fn collision_system(
+        mut cameras: Query<&mut OrbitCamera>,
+        meshes: Query<(&Aabb, &GlobalTransform), With<Handle<Mesh>>>,
+    ) {
+        for mut camera in cameras.iter_mut() {
+            let distance = match camera.collision_distance {
+                Some(distance) if camera.enabled => distance,
+                _ => continue,
+            };
+            let direction = camera.direction();
+
+            let mut max_ρ = camera.ρ;
+            for (aabb, transform) in meshes.iter() {
+                let (min, max) = world_bounds(aabb, &transform.compute_matrix());
+                if let Some(t) =
+                    ray_box_entry(camera.origin, direction, min - distance, max + distance)
+                {
+                    max_ρ = max_ρ.min(t);
+                }
+            }
+
+            if max_ρ < camera.ρ {
+                camera.ρ = max_ρ;
+                camera.target_ρ = camera.target_ρ.min(max_ρ);
             }
         }
     }
@@ -168,11 +1317,413 @@ impl OrbitCameraPlugin {
 
 impl Plugin for OrbitCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(Self::emit_motion_events)
+        app.add_system(Self::emit_input_events)
             .add_system(Self::mouse_motion_system)
-            .add_system(Self::emit_zoom_events)
+            .add_system(Self::emit_touch_events)
             .add_system(Self::zoom_system)
+            .add_system(Self::focus_system)
+            .add_system(Self::mode_system.before(Self::update_transform_system))
+            .add_system(Self::projection_system.before(Self::update_transform_system))
+            .add_system(Self::collision_system.before(Self::update_transform_system))
             .add_system(Self::update_transform_system)
-            .add_event::<CameraEvents>();
+            .register_type::<OrbitCamera>()
+            .init_resource::<ActiveCamera>()
+            .add_event::<CameraEvent>()
+            .add_event::<FocusCamera>();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::camera::{CameraProjection, Viewport};
+
+    #[test]
+    fn aligned_views_look_along_axes() {
+        for (view, position) in [
+            (AlignedView::Front, Vec3::Z),
+            (AlignedView::Back, -Vec3::Z),
+            (AlignedView::Right, Vec3::X),
+            (AlignedView::Left, -Vec3::X),
+            (AlignedView::Top, Vec3::Y),
+            (AlignedView::Bottom, -Vec3::Y),
+        ] {
+            let (θ, ϕ) = view.angles();
+            let camera = OrbitCamera {
+                θ, ϕ, ..default()
+            };
+            assert!(
+                camera.direction().distance(position) < 1e-5,
+                "{:?} is seen from {}",
+                view,
+                camera.direction()
+            );
+        }
+    }
+
+    #[test]
+    fn with_radius_keeps_the_eye() {
+        let state = OrbitState {
+            origin: Vec3::new(1.0, 0.0, -2.0),
+            θ: 0.7,
+            ϕ: 1.2,
+            ρ: 5.0,
+        };
+        let fly = state.with_radius(FLY_RADIUS);
+        assert!(fly.eye().distance(state.eye()) < 1e-5);
+        assert_eq!((fly.θ, fly.ϕ, fly.ρ), (state.θ, state.ϕ, FLY_RADIUS));
+    }
+
+    #[test]
+    fn from_transform_keeps_the_eye() {
+        let origin = Vec3::new(0.5, 0.0, 0.0);
+        let transform = Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(origin, Vec3::Y);
+        let camera = OrbitCamera::from_transform(&transform, origin);
+        assert!(camera.state().eye().distance(transform.translation) < 1e-4);
+        assert_eq!(camera.state(), camera.home);
+    }
+
+    #[test]
+    fn switching_to_fly_keeps_the_eye() {
+        let mut camera = OrbitCamera::default()
+            .with_ranges(None, 0.1..=3.0, 2.0..=10.0)
+            .with_origin_bounds(Vec3::splat(-1.0), Vec3::splat(1.0));
+        let state = camera.state();
+        camera.mode = ControllerMode::Fly;
+        camera.jump_to(state.with_radius(FLY_RADIUS));
+        assert!(camera.state().eye().distance(state.eye()) < 1e-4);
+        assert_eq!(camera.state().ρ, FLY_RADIUS);
+    }
+
+    #[test]
+    fn wrapping_θ_takes_the_short_way() {
+        let mut camera = OrbitCamera {
+            θ: 0.1,
+            target_θ: 0.1,
+            ..default()
+        };
+        camera.set_target(OrbitState {
+            θ: TAU - 0.1,
+            ..camera.state()
+        });
+        // turns 0.2 back across 0 instead of almost a full turn forward
+        assert!((camera.target_θ - camera.θ + 0.2).abs() < 1e-5);
+        assert!((camera.target_θ.rem_euclid(TAU) - (TAU - 0.1)).abs() < 1e-5);
+
+        // wrapping the target past a full turn does not spin the camera back
+        let mut camera = OrbitCamera {
+            θ: TAU - 0.1,
+            target_θ: TAU + 0.1,
+            ..default()
+        };
+        camera.constrain_targets();
+        assert!((camera.target_θ - 0.1).abs() < 1e-5);
+        assert!((camera.target_θ - camera.θ - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn targets_are_clamped_to_the_ranges() {
+        let mut camera = OrbitCamera::default()
+            .with_ranges(Some(-1.0..=1.0), 0.5..=2.0, 2.0..=10.0)
+            .with_origin_bounds(Vec3::new(1.0, 1.0, 1.0), Vec3::new(-1.0, -1.0, -1.0));
+        camera.target_θ = 2.0;
+        camera.target_ϕ = 0.1;
+        camera.target_ρ = 20.0;
+        camera.target_scale = 0.5;
+        camera.target_origin = Vec3::new(3.0, -3.0, 0.5);
+        camera.constrain_targets();
+        assert_eq!(camera.target_θ, 1.0);
+        assert_eq!(camera.target_ϕ, 0.5);
+        assert_eq!(camera.target_ρ, 10.0);
+        assert_eq!(camera.target_scale, 2.0);
+        assert_eq!(camera.target_origin, Vec3::new(1.0, -1.0, 0.5));
+
+        camera.target_θ = -2.0;
+        camera.target_ϕ = 3.0;
+        camera.target_ρ = 1.0;
+        camera.constrain_targets();
+        assert_eq!(camera.target_θ, -1.0);
+        assert_eq!(camera.target_ϕ, 2.0);
+        assert_eq!(camera.target_ρ, 2.0);
+    }
+
+    #[test]
+    fn ray_enters_box() {
+        let (min, max) = (Vec3::splat(-1.0), Vec3::splat(1.0));
+        let entry = ray_box_entry(Vec3::new(-3.0, 0.5, 0.0), Vec3::X, min, max);
+        assert_eq!(entry, Some(2.0));
+        let diagonal = Vec3::ONE.normalize();
+        let entry = ray_box_entry(Vec3::splat(-2.0), diagonal, min, max).unwrap();
+        assert!((entry - 3f32.sqrt()).abs() < 1e-5);
+        // behind, beside and from inside
+        assert_eq!(
+            ray_box_entry(Vec3::new(3.0, 0.0, 0.0), Vec3::X, min, max),
+            None
+        );
+        assert_eq!(
+            ray_box_entry(Vec3::new(-3.0, 2.0, 0.0), Vec3::X, min, max),
+            None
+        );
+        assert_eq!(ray_box_entry(Vec3::ZERO, Vec3::X, min, max), None);
+    }
+
+    #[test]
+    fn axis_parallel_ray_on_a_face() {
+        let (min, max) = (Vec3::splat(-1.0), Vec3::splat(1.0));
+        // the zero direction components would give 0 × ∞
+        let entry = ray_box_entry(Vec3::new(-3.0, 1.0, -1.0), Vec3::X, min, max);
+        assert_eq!(entry, Some(2.0));
+        let entry = ray_box_entry(Vec3::new(-1.0, -3.0, 1.0), Vec3::Y, min, max);
+        assert_eq!(entry, Some(2.0));
+    }
+
+    /// NDC of a point in the view of the camera targets
+    fn target_ndc(camera: &OrbitCamera, projection: &Projection, point: Vec3) -> Vec2 {
+        let target = OrbitState {
+            origin: camera.target_origin,
+            θ: camera.target_θ,
+            ϕ: camera.target_ϕ,
+            ρ: camera.target_ρ,
+        };
+        let view = Transform::from_translation(target.eye()).looking_at(target.origin, Vec3::Y);
+        let mut projection = projection.clone();
+        if let Projection::Orthographic(orthographic) = &mut projection {
+            orthographic.scale = camera.target_scale;
+        }
+        let clip = projection.get_projection_matrix()
+            * view.compute_matrix().inverse()
+            * point.extend(1.0);
+        clip.truncate().truncate() / clip.w
+    }
+
+    #[test]
+    fn zoom_keeps_the_focus_in_place() {
+        let transform = Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::X, Vec3::Y);
+        let focus = Vec2::new(0.5, -0.25);
+        for projection in [
+            Projection::Perspective(PerspectiveProjection {
+                aspect_ratio: 2.0,
+                ..default()
+            }),
+            Projection::Orthographic(default()),
+        ] {
+            let mut camera = OrbitCamera::from_transform(&transform, Vec3::X);
+            let half_extents = camera.focal_half_extents(&projection);
+            let point =
+                camera.target_origin + transform.rotation * (focus * half_extents).extend(0.0);
+            assert!(target_ndc(&camera, &projection, point).distance(focus) < 1e-4);
+
+            let before = (camera.target_ρ, camera.target_scale);
+            camera.zoom(0.5, Some(focus), transform.rotation, &projection);
+            assert_ne!((camera.target_ρ, camera.target_scale), before);
+            assert!(
+                target_ndc(&camera, &projection, point).distance(focus) < 1e-4,
+                "{:?} moves the focus",
+                projection
+            );
+        }
+    }
+
+    #[test]
+    fn pan_follows_the_pointer() {
+        let transform = Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let mut camera = Box::new(OrbitCamera::from_transform(&transform, Vec3::ZERO));
+        let projection = Projection::Perspective(PerspectiveProjection {
+            aspect_ratio: 2.0,
+            ..default()
+        });
+        let viewport = Vec2::new(200.0, 100.0);
+        let pixel_size = camera.pixel_size(&projection, Some(viewport));
+
+        // y down like the cursor
+        let delta = Vec2::new(30.0, -10.0);
+        let offset = pan_offset(delta, pixel_size, transform.rotation);
+        apply_motion(&mut camera, None, Some(offset), 1.0 / 60.0);
+
+        let moved = camera.target_origin.length();
+        assert!((moved - (delta * pixel_size).length()).abs() < 1e-4);
+        // the point that was at the center is now where the pointer moved to
+        let ndc = Vec2::new(delta.x, -delta.y) * 2.0 / viewport;
+        assert!(target_ndc(&camera, &projection, Vec3::ZERO).distance(ndc) < 1e-4);
+    }
+
+    #[test]
+    fn fitted_sphere_touches_the_narrower_side() {
+        let radius = 2.0;
+        for aspect_ratio in [2.0, 0.5] {
+            let perspective = PerspectiveProjection {
+                aspect_ratio,
+                ..default()
+            };
+            let half_fov_y = perspective.fov * 0.5;
+            let half_fov_x = (half_fov_y.tan() * aspect_ratio).atan();
+            let distance = fit_distance(&Projection::Perspective(perspective), radius).unwrap();
+            // half the angle the sphere covers from that distance
+            let half_angle = (radius / distance).asin();
+            assert!(
+                (half_angle - half_fov_x.min(half_fov_y)).abs() < 1e-5,
+                "aspect ratio {}",
+                aspect_ratio
+            );
+        }
+        assert_eq!(
+            fit_distance(&Projection::Orthographic(default()), radius),
+            None
+        );
+    }
+
+    #[test]
+    fn entity_bounds_include_the_descendants() {
+        #[derive(Resource)]
+        struct Root(Entity);
+        #[derive(Resource, Default)]
+        struct Bounds(Option<(Vec3, Vec3)>);
+
+        fn bounds_system(
+            root: Res<Root>,
+            mut bounds: ResMut<Bounds>,
+            meshes: Query<(&Aabb, &GlobalTransform)>,
+            children: Query<&Children>,
+        ) {
+            bounds.0 = entity_bounds(root.0, &meshes, &children);
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Bounds>().add_system(bounds_system);
+
+        let aabb = Aabb::from_min_max(Vec3::splat(-1.0), Vec3::splat(1.0));
+        let grandchild = app
+            .world
+            .spawn()
+            .insert(aabb.clone())
+            .insert(GlobalTransform::from(
+                Transform::from_xyz(0.0, -4.0, 0.0).with_scale(Vec3::splat(2.0)),
+            ))
+            .id();
+        // a child without bounds still passes on those of its children
+        let empty_child = app.world.spawn().push_children(&[grandchild]).id();
+        let child = app
+            .world
+            .spawn()
+            .insert(aabb.clone())
+            .insert(GlobalTransform::from_xyz(5.0, 0.0, 0.0))
+            .id();
+        let root = app
+            .world
+            .spawn()
+            .insert(aabb)
+            .insert(GlobalTransform::default())
+            .push_children(&[child, empty_child])
+            .id();
+
+        app.insert_resource(Root(root));
+        app.update();
+        assert_eq!(
+            app.world.resource::<Bounds>().0,
+            Some((Vec3::new(-2.0, -6.0, -2.0), Vec3::new(6.0, 1.0, 2.0)))
+        );
+
+        app.insert_resource(Root(app.world.spawn().id()));
+        app.update();
+        assert_eq!(app.world.resource::<Bounds>().0, None);
+    }
+
+    /// NDC of a point in physical pixels in the viewport of a camera
+    fn viewport_point_ndc(camera: &Camera, point: Vec2) -> Option<Vec2> {
+        let viewport = camera.viewport.as_ref()?;
+        let min = viewport.physical_position.as_vec2();
+        let max = min + viewport.physical_size.as_vec2();
+        (point.cmpge(min).all() && point.cmple(max).all())
+            .then(|| (point - min) / (max - min) * 2.0 - Vec2::ONE)
+    }
+
+    #[test]
+    fn hovered_camera_in_split_screen() {
+        let viewport = |x: u32, width: u32| {
+            Some(Viewport {
+                physical_position: UVec2::new(x, 0),
+                physical_size: UVec2::new(width, 100),
+                ..default()
+            })
+        };
+        let cameras = [
+            Camera {
+                viewport: viewport(0, 100),
+                ..default()
+            },
+            Camera {
+                viewport: viewport(100, 100),
+                ..default()
+            },
+            // picture in picture over the right view
+            Camera {
+                viewport: viewport(150, 50),
+                priority: 1,
+                ..default()
+            },
+            // inactive cameras are never hovered
+            Camera {
+                viewport: viewport(0, 200),
+                priority: 2,
+                is_active: false,
+                ..default()
+            },
+        ];
+        let hovered = |x: f32| {
+            hovered_camera(
+                cameras
+                    .iter()
+                    .enumerate()
+                    .map(|(i, camera)| (Entity::from_raw(i as u32), camera)),
+                |camera| viewport_point_ndc(camera, Vec2::new(x, 50.0)),
+            )
+        };
+        assert_eq!(hovered(50.0), Some(Entity::from_raw(0)));
+        assert_eq!(hovered(120.0), Some(Entity::from_raw(1)));
+        assert_eq!(hovered(170.0), Some(Entity::from_raw(2)));
+        assert_eq!(hovered(250.0), None);
+    }
+
+    #[test]
+    fn drags_stay_on_their_camera() {
+        let (a, b) = (Entity::from_raw(0), Entity::from_raw(1));
+        assert_eq!(active_camera(Some(a), Some(b), false), Some(b));
+        // a drag started on a camera keeps moving it over another or outside
+        assert_eq!(active_camera(Some(a), Some(b), true), Some(a));
+        assert_eq!(active_camera(Some(a), None, true), Some(a));
+        // without an active camera a drag takes the hovered one
+        assert_eq!(active_camera(None, Some(b), true), Some(b));
+        // the last camera keeps the inputs while none is hovered
+        assert_eq!(active_camera(Some(a), None, false), Some(a));
+        assert_eq!(active_camera(None, None, false), None);
+    }
+
+    /// Drags the total distance at a frame rate, then releases for a second
+    fn drag(frame_rate: u32, total: Vec2) -> (f32, f32) {
+        let mut camera = Box::new(OrbitCamera::default());
+        let delta_seconds = 1.0 / frame_rate as f32;
+        for _ in 0..frame_rate {
+            apply_motion(
+                &mut camera,
+                Some(total / frame_rate as f32),
+                None,
+                delta_seconds,
+            );
+        }
+        let dragged = camera.target_θ;
+        for _ in 0..frame_rate {
+            apply_motion(&mut camera, None, None, delta_seconds);
+        }
+        (dragged, camera.target_θ)
+    }
+
+    #[test]
+    fn orbit_is_independent_of_frame_rate() {
+        let total = Vec2::new(60.0, 0.0);
+        let (dragged_60, released_60) = drag(60, total);
+        let (dragged_144, released_144) = drag(144, total);
+        assert!((dragged_60 - dragged_144).abs() < 1e-4);
+        // the momentum is integrated per frame, so only close
+        assert!((released_60 - released_144).abs() < 0.05 * (released_60 - dragged_60).abs());
+    }
+}
@@ -0,0 +1,488 @@
+//! Gesture recognition on top of the [Touches] resource.
+//!
+//! [GesturePlugin] turns the touches of each frame into [Gesture] events: taps,
+//! double taps, long presses, swipes and the two finger pinch, rotate and pan.
+//! The thresholds are configured with the [GestureSettings] resource.
+//!
+//! Positions are in the same window coordinates as the touches, y up like the
+//! cursor.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{Touch, Touches};
+
+/// A recognised gesture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A short touch that did not move
+    Tap {
+        /// Touch position
+        position: Vec2,
+    },
+    /// A second tap shortly after and close to the previous one
+    DoubleTap {
+        /// Position of the second tap
+        position: Vec2,
+    },
+    /// A touch held without moving. Sent once while the touch is still held.
+    LongPress {
+        /// Touch position
+        position: Vec2,
+    },
+    /// A fast touch movement
+    Swipe {
+        /// Main direction of the movement
+        direction: SwipeDirection,
+        /// Average velocity in pixels per second
+        velocity: Vec2,
+    },
+    /// Two touches moving closer or apart
+    Pinch {
+        /// Distance between the touches relative to the previous frame,
+        /// greater than 1 when moving apart.
+        scale: f32,
+        /// Middle point between the touches
+        center: Vec2,
+    },
+    /// Two touches rotating around each other
+    Rotate {
+        /// Angle rotated since the previous frame in radians, counterclockwise
+        angle: f32,
+        /// Middle point between the touches
+        center: Vec2,
+    },
+    /// Two touches moving in the same direction
+    TwoFingerPan {
+        /// Movement of the middle point since the previous frame
+        delta: Vec2,
+    },
+}
+
+/// Main direction of a [Gesture::Swipe]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+    /// Towards negative x
+    Left,
+    /// Towards positive x
+    Right,
+    /// Towards positive y
+    Up,
+    /// Towards negative y
+    Down,
+}
+
+impl SwipeDirection {
+    /// Dominant direction of a movement
+    pub fn from_delta(delta: Vec2) -> Self {
+        if delta.x.abs() >= delta.y.abs() {
+            if delta.x < 0.0 {
+                SwipeDirection::Left
+            } else {
+                SwipeDirection::Right
+            }
+        } else if delta.y < 0.0 {
+            SwipeDirection::Down
+        } else {
+            SwipeDirection::Up
+        }
+    }
+}
+
+/// Thresholds of the gesture recognizer. Distances are in pixels and durations in seconds.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct GestureSettings {
+    /// Longest a tap can last
+    pub tap_max_duration: f32,
+    /// Farthest a tap, double tap or long press can move
+    pub tap_max_distance: f32,
+    /// Longest time between the taps of a double tap
+    pub double_tap_max_interval: f32,
+    /// Farthest the taps of a double tap can be
+    pub double_tap_max_distance: f32,
+    /// Time a touch must be held for a long press
+    pub long_press_duration: f32,
+    /// Shortest distance of a swipe
+    pub swipe_min_distance: f32,
+    /// Slowest average speed of a swipe, in pixels per second
+    pub swipe_min_velocity: f32,
+    /// Change of the distance between two touches needed to start pinching
+    pub pinch_min_distance: f32,
+    /// Angle two touches must rotate to start rotating, in radians
+    pub rotate_min_angle: f32,
+    /// Distance two touches must move to start panning
+    pub pan_min_distance: f32,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            tap_max_duration: 0.3,
+            tap_max_distance: 10.0,
+            double_tap_max_interval: 0.3,
+            double_tap_max_distance: 30.0,
+            long_press_duration: 0.6,
+            swipe_min_distance: 50.0,
+            swipe_min_velocity: 300.0,
+            pinch_min_distance: 10.0,
+            rotate_min_angle: 0.1,
+            pan_min_distance: 10.0,
+        }
+    }
+}
+
+/// [Plugin] that sends [Gesture] events.
+///
+/// Needs the [TouchPlugin](super::TouchPlugin).
+pub struct GesturePlugin;
+
+impl Plugin for GesturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GestureSettings>()
+            .add_event::<Gesture>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                gesture_system.after(super::touch_screen_input_system),
+            );
+    }
+}
+
+fn gesture_system(
+    touches: Res<Touches>,
+    time: Res<Time>,
+    settings: Res<GestureSettings>,
+    mut recognizer: Local<GestureRecognizer>,
+    mut gestures: EventWriter<Gesture>,
+) {
+    gestures.send_batch(recognizer.update(&touches, time.seconds_since_startup(), &settings));
+}
+
+/// Single touch being tracked
+#[derive(Debug, Clone, Copy)]
+struct TouchStart {
+    time: f64,
+    /// Another touch was down at some point, it cannot be a single touch gesture
+    multi: bool,
+    long_pressed: bool,
+}
+
+/// Two touches being tracked
+#[derive(Debug, Clone, Copy)]
+struct TwoTouches {
+    ids: (u64, u64),
+    start: TwoTouchesPose,
+    previous: TwoTouchesPose,
+    pinching: bool,
+    rotating: bool,
+    panning: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TwoTouchesPose {
+    distance: f32,
+    angle: f32,
+    center: Vec2,
+}
+
+impl TwoTouchesPose {
+    fn new(a: &Touch, b: &Touch) -> Self {
+        let between = b.position() - a.position();
+        Self {
+            distance: between.length(),
+            angle: between.y.atan2(between.x),
+            center: (a.position() + b.position()) * 0.5,
+        }
+    }
+}
+
+/// Recognizes gestures from the state of the [Touches] every frame.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    starts: HashMap<u64, TouchStart>,
+    /// Time and position of the last tap that can start a double tap
+    last_tap: Option<(f64, Vec2)>,
+    two_touches: Option<TwoTouches>,
+}
+
+impl GestureRecognizer {
+    /// Returns the gestures recognised with the touches of a frame, `now` being the
+    /// time in seconds.
+    pub fn update(
+        &mut self,
+        touches: &Touches,
+        now: f64,
+        settings: &GestureSettings,
+    ) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+
+        for touch in touches.iter_just_pressed() {
+            self.starts.insert(
+                touch.id(),
+                TouchStart {
+                    time: now,
+                    multi: false,
+                    long_pressed: false,
+                },
+            );
+        }
+        let pressed_count = touches.iter().count();
+        if pressed_count > 1 {
+            for start in self.starts.values_mut() {
+                start.multi = true;
+            }
+        }
+
+        self.long_presses(touches, now, settings, &mut gestures);
+        self.releases(touches, now, settings, &mut gestures);
+        self.two_touches(touches, settings, &mut gestures);
+
+        gestures
+    }
+
+    fn long_presses(
+        &mut self,
+        touches: &Touches,
+        now: f64,
+        settings: &GestureSettings,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        for touch in touches.iter() {
+            let start = match self.starts.get_mut(&touch.id()) {
+                Some(start) if !start.multi && !start.long_pressed => start,
+                _ => continue,
+            };
+            if touch.distance().length() > settings.tap_max_distance {
+                continue;
+            }
+            if now - start.time >= settings.long_press_duration as f64 {
+                start.long_pressed = true;
+                gestures.push(Gesture::LongPress {
+                    position: touch.position(),
+                });
+            }
+        }
+    }
+
+    fn releases(
+        &mut self,
+        touches: &Touches,
+        now: f64,
+        settings: &GestureSettings,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        for touch in touches.iter_just_cancelled() {
+            self.starts.remove(&touch.id());
+        }
+
+        for touch in touches.iter_just_released() {
+            let start = match self.starts.remove(&touch.id()) {
+                Some(start) if !start.multi && !start.long_pressed => start,
+                _ => continue,
+            };
+            let duration = (now - start.time) as f32;
+            let distance = touch.distance();
+
+            if distance.length() <= settings.tap_max_distance
+                && duration <= settings.tap_max_duration
+            {
+                let position = touch.position();
+                gestures.push(Gesture::Tap { position });
+
+                match self.last_tap {
+                    Some((time, last_position))
+                        if (now - time) as f32 <= settings.double_tap_max_interval
+                            && position.distance(last_position)
+                                <= settings.double_tap_max_distance =>
+                    {
+                        gestures.push(Gesture::DoubleTap { position });
+                        // a third tap starts over
+                        self.last_tap = None;
+                    }
+                    _ => self.last_tap = Some((now, position)),
+                }
+            } else if distance.length() >= settings.swipe_min_distance {
+                let velocity = distance / duration.max(f32::EPSILON);
+                if velocity.length() >= settings.swipe_min_velocity {
+                    gestures.push(Gesture::Swipe {
+                        direction: SwipeDirection::from_delta(distance),
+                        velocity,
+                    });
+                }
+            }
+        }
+    }
+
+    fn two_touches(
+        &mut self,
+        touches: &Touches,
+        settings: &GestureSettings,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        let mut pressed: Vec<&Touch> = touches.iter().collect();
+        if pressed.len() != 2 {
+            self.two_touches = None;
+            return;
+        }
+        pressed.sort_by_key(|touch| touch.id());
+        let (a, b) = (pressed[0], pressed[1]);
+        let pose = TwoTouchesPose::new(a, b);
+
+        let ids = (a.id(), b.id());
+        match &mut self.two_touches {
+            Some(state) if state.ids == ids => state.update(pose, settings, gestures),
+            two_touches => {
+                *two_touches = Some(TwoTouches {
+                    ids,
+                    start: pose,
+                    previous: pose,
+                    pinching: false,
+                    rotating: false,
+                    panning: false,
+                })
+            }
+        }
+    }
+}
+
+impl TwoTouches {
+    fn update(
+        &mut self,
+        pose: TwoTouchesPose,
+        settings: &GestureSettings,
+        gestures: &mut Vec<Gesture>,
+    ) {
+        self.pinching |= (pose.distance - self.start.distance).abs() >= settings.pinch_min_distance;
+        self.rotating |=
+            wrap_angle(pose.angle - self.start.angle).abs() >= settings.rotate_min_angle;
+        self.panning |= pose.center.distance(self.start.center) >= settings.pan_min_distance;
+
+        let previous = self.previous;
+        if self.pinching && previous.distance > 0.0 && pose.distance != previous.distance {
+            gestures.push(Gesture::Pinch {
+                scale: pose.distance / previous.distance,
+                center: pose.center,
+            });
+        }
+        let angle = wrap_angle(pose.angle - previous.angle);
+        if self.rotating && angle != 0.0 {
+            gestures.push(Gesture::Rotate {
+                angle,
+                center: pose.center,
+            });
+        }
+        let delta = pose.center - previous.center;
+        if self.panning && delta != Vec2::ZERO {
+            gestures.push(Gesture::TwoFingerPan { delta });
+        }
+
+        self.previous = pose;
+    }
+}
+
+/// Wraps an angle to -π..π
+fn wrap_angle(angle: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::touch::{TouchInput, TouchPhase};
+
+    use super::*;
+
+    fn touch(touches: &mut Touches, id: u64, phase: TouchPhase, position: Vec2) {
+        touches.process_touch_event(&TouchInput {
+            phase,
+            position,
+            force: None,
+            id,
+        });
+    }
+
+    #[test]
+    fn tap_and_double_tap() {
+        let settings = GestureSettings::default();
+        let mut recognizer = GestureRecognizer::default();
+        let mut touches = Touches::default();
+        let position = Vec2::new(100.0, 100.0);
+
+        touch(&mut touches, 1, TouchPhase::Started, position);
+        assert!(recognizer.update(&touches, 0.0, &settings).is_empty());
+        touches.update();
+        touch(&mut touches, 1, TouchPhase::Ended, position);
+        assert_eq!(
+            recognizer.update(&touches, 0.1, &settings),
+            vec![Gesture::Tap { position }]
+        );
+
+        touches.update();
+        touch(&mut touches, 2, TouchPhase::Started, position);
+        recognizer.update(&touches, 0.2, &settings);
+        touches.update();
+        touch(&mut touches, 2, TouchPhase::Ended, position);
+        assert_eq!(
+            recognizer.update(&touches, 0.3, &settings),
+            vec![Gesture::Tap { position }, Gesture::DoubleTap { position }]
+        );
+    }
+
+    #[test]
+    fn swipe() {
+        let settings = GestureSettings::default();
+        let mut recognizer = GestureRecognizer::default();
+        let mut touches = Touches::default();
+
+        touch(&mut touches, 1, TouchPhase::Started, Vec2::ZERO);
+        recognizer.update(&touches, 0.0, &settings);
+        touches.update();
+        touch(&mut touches, 1, TouchPhase::Moved, Vec2::new(-100.0, 10.0));
+        recognizer.update(&touches, 0.05, &settings);
+        touches.update();
+        touch(&mut touches, 1, TouchPhase::Ended, Vec2::new(-200.0, 10.0));
+
+        match recognizer.update(&touches, 0.1, &settings).as_slice() {
+            [Gesture::Swipe {
+                direction,
+                velocity,
+            }] => {
+                assert_eq!(*direction, SwipeDirection::Left);
+                assert!((velocity.x + 2000.0).abs() < 1e-2);
+            }
+            gestures => panic!("expected a swipe, got {:?}", gestures),
+        }
+    }
+
+    #[test]
+    fn pinch_and_rotate() {
+        let settings = GestureSettings::default();
+        let mut recognizer = GestureRecognizer::default();
+        let mut touches = Touches::default();
+
+        touch(&mut touches, 1, TouchPhase::Started, Vec2::new(-50.0, 0.0));
+        touch(&mut touches, 2, TouchPhase::Started, Vec2::new(50.0, 0.0));
+        recognizer.update(&touches, 0.0, &settings);
+
+        // move apart
+        touches.update();
+        touch(&mut touches, 1, TouchPhase::Moved, Vec2::new(-100.0, 0.0));
+        touch(&mut touches, 2, TouchPhase::Moved, Vec2::new(100.0, 0.0));
+        assert_eq!(
+            recognizer.update(&touches, 0.1, &settings),
+            vec![Gesture::Pinch {
+                scale: 2.0,
+                center: Vec2::ZERO
+            }]
+        );
+
+        // quarter turn counterclockwise
+        touches.update();
+        touch(&mut touches, 1, TouchPhase::Moved, Vec2::new(0.0, -100.0));
+        touch(&mut touches, 2, TouchPhase::Moved, Vec2::new(0.0, 100.0));
+        match recognizer.update(&touches, 0.2, &settings).as_slice() {
+            [Gesture::Rotate { angle, .. }] => {
+                assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-5)
+            }
+            gestures => panic!("expected a rotation, got {:?}", gestures),
+        }
+    }
+}
@@ -6,9 +6,44 @@ use bevy::{
         ButtonState,
     },
     math::Vec2,
+    prelude::*,
     utils::HashMap,
 };
 
+use crate::plugins::mouse::window_ndc;
+
+pub mod gestures;
+
+/// [Plugin] that keeps the [Touches] resource up to date with the [TouchInput] events.
+///
+/// Touch positions are converted to normalised device coordinates of the primary
+/// window, as the [Mouse](crate::plugins::mouse::Mouse) does with the cursor.
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Touches>()
+            .add_system_to_stage(CoreStage::PreUpdate, touch_screen_input_system);
+    }
+}
+
+/// Updates the [Touches] resource with the [TouchInput] events of this frame.
+pub fn touch_screen_input_system(
+    mut touches: ResMut<Touches>,
+    mut touch_input_events: EventReader<TouchInput>,
+    windows: Option<Res<Windows>>,
+) {
+    touches.update();
+    for event in touch_input_events.iter() {
+        touches.process_touch_event(event);
+    }
+
+    // touch events carry no window, they are on the primary one
+    if let Some(window) = windows.as_ref().and_then(|windows| windows.get_primary()) {
+        touches.update_ndc(|position| window_ndc(window, position));
+    }
+}
+
 fn mouse_button_events(mut mousebtn_evr: EventReader<MouseButtonInput>) {
     for ev in mousebtn_evr.iter() {
         match ev.state {
@@ -75,6 +110,8 @@ pub struct Touch {
     position: Vec2,
     /// The current force of the touch input.
     force: Option<ForceTouch>,
+    /// The current position in normalised device coordinates of the window.
+    normalised_device_coordinates: Vec2,
 }
 
 impl Touch {
@@ -129,6 +166,12 @@ impl Touch {
     pub fn force(&self) -> Option<ForceTouch> {
         self.force
     }
+
+    /// Returns the current position in normalised device coordinates of the window, y up.
+    #[inline]
+    pub fn normalised_device_coordinates(&self) -> Vec2 {
+        self.normalised_device_coordinates
+    }
 }
 
 impl From<&TouchInput> for Touch {
@@ -141,6 +184,7 @@ impl From<&TouchInput> for Touch {
             previous_force: input.force,
             position: input.position,
             force: input.force,
+            normalised_device_coordinates: Vec2::ZERO,
         }
     }
 }
@@ -154,8 +198,8 @@ impl From<&TouchInput> for Touch {
 ///
 /// ## Updating
 ///
-/// The resource is updated inside of the [`touch_screen_input_system`](crate::touch::touch_screen_input_system).
-#[derive(Debug, Clone, Default)]
+/// The resource is updated inside of the [`touch_screen_input_system`], added by the [`TouchPlugin`].
+#[derive(Resource, Debug, Clone, Default)]
 pub struct Touches {
     /// A collection of every [`Touch`] that is currently being pressed.
     pressed: HashMap<u64, Touch>,
@@ -231,16 +275,44 @@ impl Touches {
                 }
             }
             TouchPhase::Ended => {
-                self.just_released.insert(event.id, event.into());
-                self.pressed.remove_entry(&event.id);
+                let touch = self.end_touch(event);
+                self.just_released.insert(event.id, touch);
             }
             TouchPhase::Cancelled => {
-                self.just_cancelled.insert(event.id, event.into());
-                self.pressed.remove_entry(&event.id);
+                let touch = self.end_touch(event);
+                self.just_cancelled.insert(event.id, touch);
             }
         };
     }
 
+    /// Removes a touch from the `pressed` collection, keeping its start position.
+    fn end_touch(&mut self, event: &TouchInput) -> Touch {
+        match self.pressed.remove(&event.id) {
+            Some(mut touch) => {
+                touch.previous_position = touch.position;
+                touch.previous_force = touch.force;
+                touch.position = event.position;
+                touch.force = event.force;
+                touch
+            }
+            None => event.into(),
+        }
+    }
+
+    /// Converts the current position of every touch to normalised device coordinates.
+    fn update_ndc(&mut self, to_ndc: impl Fn(Vec2) -> Vec2) {
+        for touches in [
+            &mut self.pressed,
+            &mut self.just_pressed,
+            &mut self.just_released,
+            &mut self.just_cancelled,
+        ] {
+            for touch in touches.values_mut() {
+                touch.normalised_device_coordinates = to_ndc(touch.position);
+            }
+        }
+    }
+
     /// Clears the `just_pressed`, `just_released`, and `just_cancelled` collections.
     ///
     /// This is not clearing the `pressed` collection, because it could incorrectly mark
@@ -254,3 +326,75 @@ impl Touches {
         self.just_cancelled.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch_input(id: u64, phase: TouchPhase, position: Vec2) -> TouchInput {
+        TouchInput {
+            phase,
+            position,
+            force: None,
+            id,
+        }
+    }
+
+    #[test]
+    fn touch_lifecycle() {
+        let mut touches = Touches::default();
+
+        touches.process_touch_event(&touch_input(1, TouchPhase::Started, Vec2::new(1.0, 2.0)));
+        assert!(touches.just_pressed(1));
+        assert_eq!(
+            touches.get_pressed(1).unwrap().position(),
+            Vec2::new(1.0, 2.0)
+        );
+
+        touches.update();
+        touches.process_touch_event(&touch_input(1, TouchPhase::Moved, Vec2::new(4.0, 6.0)));
+        let touch = touches.get_pressed(1).unwrap();
+        assert!(!touches.just_pressed(1));
+        assert_eq!(touch.delta(), Vec2::new(3.0, 4.0));
+        assert_eq!(touch.start_position(), Vec2::new(1.0, 2.0));
+
+        touches.update();
+        touches.process_touch_event(&touch_input(1, TouchPhase::Ended, Vec2::new(5.0, 6.0)));
+        assert!(touches.get_pressed(1).is_none());
+        let released = touches.get_released(1).unwrap();
+        assert_eq!(released.distance(), Vec2::new(4.0, 4.0));
+
+        touches.update();
+        assert!(!touches.just_released(1));
+    }
+
+    #[test]
+    fn cancelled_touch() {
+        let mut touches = Touches::default();
+
+        touches.process_touch_event(&touch_input(1, TouchPhase::Started, Vec2::ZERO));
+        touches.process_touch_event(&touch_input(2, TouchPhase::Started, Vec2::ONE));
+        touches.process_touch_event(&touch_input(2, TouchPhase::Cancelled, Vec2::ONE));
+
+        assert!(touches.just_cancelled(2));
+        assert_eq!(touches.iter().count(), 1);
+        assert!(touches.get_pressed(1).is_some());
+    }
+
+    #[test]
+    fn touch_ndc() {
+        let mut touches = Touches::default();
+        let window_size = Vec2::new(100.0, 50.0);
+
+        touches.process_touch_event(&touch_input(1, TouchPhase::Started, Vec2::new(75.0, 25.0)));
+        touches.update_ndc(|position| position / window_size * 2.0 - Vec2::ONE);
+
+        assert_eq!(
+            touches
+                .get_pressed(1)
+                .unwrap()
+                .normalised_device_coordinates(),
+            Vec2::new(0.5, 0.0)
+        );
+    }
+}
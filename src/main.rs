@@ -8,13 +8,20 @@ use bevy::{
     prelude::*,
     window::{PresentMode, WindowDescriptor, WindowMode, WindowResizeConstraints},
 };
-use camera::pan_orbit_camera::{OrbitCamera, OrbitCameraPlugin};
+use camera::{
+    bookmarks::{BookmarkEvent, CameraBookmarksPlugin},
+    pan_orbit_camera::{ActiveCamera, FocusCamera, FocusTarget, OrbitCamera, OrbitCameraPlugin},
+};
+use events::{gestures::GesturePlugin, TouchPlugin};
 use material::{
     animated_material::{setup_animated_cubes, AnimatedMaterialPlugin},
     game_of_life::GameOfLifePlugin,
     shader_material::CustomMaterial,
 };
-use plugins::mouse::MousePlugin;
+use plugins::{
+    mouse::MousePlugin,
+    picking::{MeshClicked, PickingPlugin},
+};
 
 fn main() {
     let mut app = App::new();
@@ -40,7 +47,11 @@ fn main() {
     })
     .add_plugins(DefaultPlugins)
     .add_plugin(MousePlugin)
+    .add_plugin(PickingPlugin)
+    .add_plugin(TouchPlugin)
+    .add_plugin(GesturePlugin)
     .add_plugin(OrbitCameraPlugin)
+    .add_plugin(CameraBookmarksPlugin::default())
     .add_plugin(AnimatedMaterialPlugin)
     .add_plugin(GameOfLifePlugin)
     .insert_resource(AssetServerSettings {
@@ -48,7 +59,9 @@ fn main() {
         ..default()
     })
     .add_startup_system(setup_animated_cubes)
-    .add_startup_system(lights_camera_action);
+    .add_startup_system(lights_camera_action)
+    .add_system(frame_selected)
+    .add_system(camera_bookmarks);
 
     // .add_system(update_custom_material);
 
@@ -61,12 +74,13 @@ fn main() {
 fn lights_camera_action(mut commands: Commands) {
     // load a texture and retrieve its aspect ratio
 
+    let transform = Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
     commands
         .spawn_bundle(Camera3dBundle {
-            transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            transform,
             ..default()
         })
-        .insert(OrbitCamera::default());
+        .insert(OrbitCamera::from_transform(&transform, Vec3::ZERO));
 
     // light
     commands.spawn_bundle(PointLightBundle {
@@ -80,6 +94,61 @@ fn lights_camera_action(mut commands: Commands) {
     });
 }
 
+/// Frames the mesh clicked last with the active camera when pressing F
+fn frame_selected(
+    keys: Res<Input<KeyCode>>,
+    active: Res<ActiveCamera>,
+    mut clicked: EventReader<MeshClicked>,
+    mut selected: Local<Option<Entity>>,
+    mut focus: EventWriter<FocusCamera>,
+) {
+    if let Some(event) = clicked.iter().last() {
+        *selected = Some(event.entity);
+    }
+    if let (true, Some(entity)) = (keys.just_pressed(KeyCode::F), *selected) {
+        focus.send(FocusCamera {
+            camera: active.0,
+            target: FocusTarget::Entity(entity),
+        });
+    }
+}
+
+/// Ctrl + 1 to 4 saves the view of the active camera, 1 to 4 moves it back to it
+fn camera_bookmarks(
+    keys: Res<Input<KeyCode>>,
+    active: Res<ActiveCamera>,
+    cameras: Query<Entity, With<OrbitCamera>>,
+    mut events: EventWriter<BookmarkEvent>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::LControl, KeyCode::RControl]);
+    let slots = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+    for (slot, key) in slots.into_iter().enumerate() {
+        if !keys.just_pressed(key) {
+            continue;
+        }
+        let name = format!("view {}", slot + 1);
+        // every camera until one is active
+        let targets: Vec<Entity> = match active.0 {
+            Some(camera) => vec![camera],
+            None => cameras.iter().collect(),
+        };
+        for camera in targets {
+            events.send(if ctrl {
+                BookmarkEvent::Save {
+                    camera,
+                    name: name.clone(),
+                }
+            } else {
+                BookmarkEvent::Recall {
+                    camera,
+                    name: name.clone(),
+                    duration: 0.8,
+                }
+            });
+        }
+    }
+}
+
 // fn update_custom_material(
 //     // access entities that have `Health` and `Transform` components
 //     // get read-only access to `Health` and mutable access to `Transform`
@@ -1,13 +1,10 @@
-use bitflags::bitflags;
-
-use crate::plugins::mouse::Mouse;
+use crate::plugins::{mouse::Mouse, picking::PickableMesh};
 use bevy::{
     core_pipeline::core_3d::Transparent3d,
     ecs::system::{
         lifetimeless::{Read, SRes},
         SystemParamItem,
     },
-    input::{mouse::MouseButtonInput, ButtonState},
     pbr::{
         DrawMesh, MeshPipeline, MeshPipelineKey, MeshUniform, SetMeshBindGroup,
         SetMeshViewBindGroup,
@@ -36,6 +33,7 @@ pub fn setup_animated_cubes(mut commands: Commands, mut meshes: ResMut<Assets<Me
         Transform::from_xyz(0.0, 0.5, 0.0),
         GlobalTransform::default(),
         AnimatedMaterial,
+        PickableMesh,
         Visibility::default(),
         ComputedVisibility::default(),
     ));
@@ -133,47 +131,24 @@ fn queue_custom(
     }
 }
 
-bitflags! {
-    #[repr(transparent)]
-    #[derive(ShaderType)]
-    struct MouseButtonFlag: u32 {
-        const LEFT = 0b00000001;
-        const RIGHT = 0b00000010;
-        const MIDDLE = 0b00000100;
-        const OTHER = 0b00001000;
-    }
-    #[repr(transparent)]
-    #[derive(ShaderType)]
-    struct ButtonStateFlag: u32 {
-        const PRESSED = 0b00000001;
-        const RELEASED = 0b00000010;
-    }
-}
-
-impl From<MouseButton> for MouseButtonFlag {
-    fn from(mouse_button: MouseButton) -> Self {
-        match mouse_button {
-            MouseButton::Left => MouseButtonFlag::LEFT,
-            MouseButton::Right => MouseButtonFlag::RIGHT,
-            MouseButton::Middle => MouseButtonFlag::MIDDLE,
-            MouseButton::Other(v) => MouseButtonFlag::OTHER,
-        }
-    }
-}
-impl From<ButtonState> for ButtonStateFlag {
-    fn from(button_state: ButtonState) -> Self {
-        match button_state {
-            ButtonState::Pressed => ButtonStateFlag::PRESSED,
-            ButtonState::Released => ButtonStateFlag::RELEASED,
-        }
-    }
-}
-
-#[derive(Resource, ShaderType)]
+/// Mouse state as laid out in the `Mouse` struct of `animate_shader.wgsl`
+#[derive(Resource, ShaderType, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
 struct ExtractedMouse {
-    // button: MouseButtonFlag,
-    // button_state: ButtonStateFlag,
     normalised_device_coordinates: Vec2,
+    motion: Vec2,
+    wheel: Vec2,
+    wheel_delta: Vec2,
+    drag_start: Vec2,
+    drag_delta: Vec2,
+    /// [MouseButtonFlag](crate::plugins::mouse::MouseButtonFlag) bits
+    pressed: u32,
+    /// [MouseButtonFlag](crate::plugins::mouse::MouseButtonFlag) bits
+    just_pressed: u32,
+    /// [MouseButtonFlag](crate::plugins::mouse::MouseButtonFlag) bits
+    just_released: u32,
+    /// 1 while dragging
+    dragging: u32,
 }
 
 impl ExtractResource for ExtractedMouse {
@@ -181,9 +156,18 @@ impl ExtractResource for ExtractedMouse {
 
     fn extract_resource(mouse: &Self::Source) -> Self {
         ExtractedMouse {
-            // button: mouse.button.into(),
-            // button_state: mouse.button_state.into(),
             normalised_device_coordinates: mouse.normalised_device_coordinates,
+            motion: mouse.motion,
+            wheel: mouse.wheel,
+            wheel_delta: mouse.wheel_delta,
+            drag_start: mouse
+                .drag_start
+                .unwrap_or(mouse.normalised_device_coordinates),
+            drag_delta: mouse.drag_delta,
+            pressed: mouse.pressed.bits(),
+            just_pressed: mouse.just_pressed.bits(),
+            just_released: mouse.just_released.bits(),
+            dragging: mouse.dragging() as u32,
         }
     }
 }
@@ -199,10 +183,7 @@ fn prepare_mouse(
     mouse_meta: ResMut<MouseMeta>,
     render_queue: Res<RenderQueue>,
 ) {
-    let normalised_device_coordinates_bytes =
-        bevy::core::bytes_of(&mouse.normalised_device_coordinates);
-
-    render_queue.write_buffer(&mouse_meta.buffer, 0, normalised_device_coordinates_bytes);
+    render_queue.write_buffer(&mouse_meta.buffer, 0, bevy::core::bytes_of(&*mouse));
 }
 
 // create a bind group for the time uniform buffer
@@ -1,2 +1,3 @@
 pub mod mouse;
+pub mod picking;
 pub mod pixel_buffer;
@@ -1,40 +1,323 @@
-use bevy::{ecs::system::Resource, input::mouse, prelude::Vec2, prelude::*, reflect::Reflect};
+use bitflags::bitflags;
+
+use bevy::{
+    ecs::system::Resource,
+    input::mouse::{MouseMotion, MouseWheel},
+    prelude::Vec2,
+    prelude::*,
+    reflect::Reflect,
+    utils::{HashMap, HashSet},
+    window::WindowId,
+};
+
+bitflags! {
+    /// Set of mouse buttons, as uploaded to shaders
+    #[repr(transparent)]
+    #[derive(Default)]
+    pub struct MouseButtonFlag: u32 {
+        const LEFT = 0b00000001;
+        const RIGHT = 0b00000010;
+        const MIDDLE = 0b00000100;
+        const OTHER = 0b00001000;
+    }
+}
+
+impl From<MouseButton> for MouseButtonFlag {
+    fn from(mouse_button: MouseButton) -> Self {
+        match mouse_button {
+            MouseButton::Left => MouseButtonFlag::LEFT,
+            MouseButton::Right => MouseButtonFlag::RIGHT,
+            MouseButton::Middle => MouseButtonFlag::MIDDLE,
+            MouseButton::Other(_) => MouseButtonFlag::OTHER,
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a MouseButton> for MouseButtonFlag {
+    fn from_iter<T: IntoIterator<Item = &'a MouseButton>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(MouseButtonFlag::empty(), |flags, button| {
+                flags | MouseButtonFlag::from(*button)
+            })
+    }
+}
 
 #[derive(Resource, Reflect, Debug, Clone)]
 pub struct Mouse {
-    // button: MouseButton,
-    // button_state: ButtonState,
+    /// Buttons held
+    #[reflect(ignore)]
+    pub pressed: MouseButtonFlag,
+    /// Buttons pressed this frame
+    #[reflect(ignore)]
+    pub just_pressed: MouseButtonFlag,
+    /// Buttons released this frame
+    #[reflect(ignore)]
+    pub just_released: MouseButtonFlag,
+    /// Wheel delta accumulated since the start, in lines or pixels depending on the device
+    pub wheel: Vec2,
+    /// Wheel delta of this frame
+    pub wheel_delta: Vec2,
+    /// Mouse motion of this frame, as reported by [MouseMotion]
+    pub motion: Vec2,
+    /// Normalised device coordinates where the held buttons were pressed
+    pub drag_start: Option<Vec2>,
+    /// Normalised device coordinates moved since [Mouse::drag_start], zero if not dragging
+    pub drag_delta: Vec2,
+    /// Normalised device coordinates of the cursor in the window it is in, or the
+    /// last known ones if it is outside every window.
     pub normalised_device_coordinates: Vec2,
+    /// Window the cursor is in
+    #[reflect(ignore)]
+    pub window: Option<WindowId>,
+    /// State of every window, by id
+    #[reflect(ignore)]
+    pub windows: HashMap<WindowId, WindowMouse>,
 }
 impl Default for Mouse {
     fn default() -> Mouse {
         Mouse {
+            pressed: MouseButtonFlag::empty(),
+            just_pressed: MouseButtonFlag::empty(),
+            just_released: MouseButtonFlag::empty(),
+            wheel: Vec2::ZERO,
+            wheel_delta: Vec2::ZERO,
+            motion: Vec2::ZERO,
+            drag_start: None,
+            drag_delta: Vec2::ZERO,
             normalised_device_coordinates: Vec2::new(0., 0.),
+            window: None,
+            windows: HashMap::default(),
         }
     }
 }
 
+impl Mouse {
+    /// Mouse state of a window
+    pub fn get(&self, window_id: WindowId) -> Option<&WindowMouse> {
+        self.windows.get(&window_id)
+    }
+
+    /// Mouse state of the primary window
+    pub fn primary(&self) -> Option<&WindowMouse> {
+        self.get(WindowId::primary())
+    }
+
+    /// A button is held and the cursor moved since it was pressed
+    pub fn dragging(&self) -> bool {
+        self.drag_start.is_some() && self.drag_delta != Vec2::ZERO
+    }
+}
+
+/// Mouse state relative to one window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WindowMouse {
+    /// Cursor position in logical pixels from the bottom left, as [Window::cursor_position].
+    ///
+    /// The last known position while the cursor is outside.
+    pub position: Vec2,
+    /// Cursor position in normalised device coordinates of the whole window, y up.
+    pub normalised_device_coordinates: Vec2,
+    /// The cursor is inside the window
+    pub inside: bool,
+    /// Buttons held, pressed while the cursor was inside the window
+    pub pressed: HashSet<MouseButton>,
+    /// Wheel delta of this frame while the cursor is inside the window.
+    ///
+    /// Lines or pixels depending on the device, see [MouseScrollUnit](bevy::input::mouse::MouseScrollUnit).
+    pub wheel: Vec2,
+}
+
 pub struct MousePlugin;
 
 impl Plugin for MousePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Mouse>()
-            .add_system(mouse_position_system);
+            .add_system_to_stage(CoreStage::PreUpdate, mouse_position_system);
     }
 }
 
-fn mouse_position_system(windows: Res<Windows>, mut mouse: ResMut<Mouse>) {
-    // Games typically only have one window (the primary window).
-    // For multi-window applications, you need to use a specific window ID here.
-    let window = windows.get_primary().unwrap();
+/// Converts a cursor position in logical pixels from the bottom left of a window to
+/// normalised device coordinates of the window.
+pub fn window_ndc(window: &Window, cursor_position: Vec2) -> Vec2 {
+    let window_size = Vec2::new(window.width(), window.height());
+    (cursor_position / window_size) * 2.0 - Vec2::ONE
+}
+
+fn mouse_position_system(
+    windows: Option<Res<Windows>>,
+    buttons: Option<Res<Input<MouseButton>>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut mouse: ResMut<Mouse>,
+) {
+    let mouse = mouse.as_mut();
+
+    let wheel = wheel_events
+        .iter()
+        .fold(Vec2::ZERO, |delta, ev| delta + Vec2::new(ev.x, ev.y));
+    mouse.wheel_delta = wheel;
+    mouse.wheel += wheel;
+    mouse.motion = motion_events
+        .iter()
+        .fold(Vec2::ZERO, |delta, ev| delta + ev.delta);
+
+    update_buttons(mouse, buttons.as_deref());
+
+    // headless apps have no windows
+    match windows {
+        Some(windows) => update_windows(mouse, &windows, buttons.as_deref(), wheel),
+        None => {
+            mouse.window = None;
+            mouse.windows.clear();
+        }
+    }
+
+    update_drag(mouse);
+}
 
-    if let Some(_position) = window.cursor_position() {
-        // cursor is inside the window, position given
-        let screen_pos = window.cursor_position().unwrap();
-        let window_size = Vec2::new(window.width(), window.height());
-        let ndc = (screen_pos / window_size) * 2.0 - Vec2::ONE;
-        mouse.normalised_device_coordinates = ndc;
+fn update_buttons(mouse: &mut Mouse, buttons: Option<&Input<MouseButton>>) {
+    match buttons {
+        Some(buttons) => {
+            mouse.pressed = buttons.get_pressed().collect();
+            mouse.just_pressed = buttons.get_just_pressed().collect();
+            mouse.just_released = buttons.get_just_released().collect();
+        }
+        None => {
+            mouse.pressed = MouseButtonFlag::empty();
+            mouse.just_pressed = MouseButtonFlag::empty();
+            mouse.just_released = MouseButtonFlag::empty();
+        }
+    }
+}
+
+/// Drags are measured from where the first held button was pressed
+fn update_drag(mouse: &mut Mouse) {
+    if mouse.pressed.is_empty() {
+        mouse.drag_start = None;
+        mouse.drag_delta = Vec2::ZERO;
     } else {
-        // cursor is not inside the window
+        let start = *mouse
+            .drag_start
+            .get_or_insert(mouse.normalised_device_coordinates);
+        mouse.drag_delta = mouse.normalised_device_coordinates - start;
+    }
+}
+
+fn update_windows(
+    mouse: &mut Mouse,
+    windows: &Windows,
+    buttons: Option<&Input<MouseButton>>,
+    wheel: Vec2,
+) {
+    mouse
+        .windows
+        .retain(|window_id, _| windows.get(*window_id).is_some());
+    mouse.window = None;
+
+    for window in windows.iter() {
+        let state = mouse.windows.entry(window.id()).or_default();
+        state.wheel = Vec2::ZERO;
+
+        match window.cursor_position() {
+            Some(position) => {
+                state.position = position;
+                state.normalised_device_coordinates = window_ndc(window, position);
+                state.inside = true;
+                state.wheel = wheel;
+                if let Some(buttons) = buttons {
+                    state.pressed.extend(buttons.get_just_pressed());
+                }
+
+                mouse.window = Some(window.id());
+                mouse.normalised_device_coordinates = state.normalised_device_coordinates;
+            }
+            None => state.inside = false,
+        }
+
+        // releases count even outside of the window
+        match buttons {
+            Some(buttons) => state.pressed.retain(|button| buttons.pressed(*button)),
+            None => state.pressed.clear(),
+        }
+    }
+}
+
+/// Converts a cursor position in a window, in logical pixels from the bottom left,
+/// to normalised device coordinates in the viewport of a camera rendering to it.
+///
+/// Returns [None] if the cursor is outside of the camera viewport.
+pub fn viewport_ndc(camera: &Camera, window: &Window, cursor_position: Vec2) -> Option<Vec2> {
+    let window_size = Vec2::new(window.width(), window.height());
+    let (viewport_position, viewport_size) = match &camera.viewport {
+        Some(viewport) => {
+            let scale_factor = window.scale_factor() as f32;
+            (
+                viewport.physical_position.as_vec2() / scale_factor,
+                viewport.physical_size.as_vec2() / scale_factor,
+            )
+        }
+        None => (Vec2::ZERO, window_size),
+    };
+
+    // viewports are positioned from the top left
+    let top_left_cursor = Vec2::new(cursor_position.x, window_size.y - cursor_position.y);
+    let local = top_left_cursor - viewport_position;
+    if local.cmplt(Vec2::ZERO).any() || local.cmpgt(viewport_size).any() {
+        return None;
+    }
+
+    let ndc = local / viewport_size * 2.0 - Vec2::ONE;
+    Some(Vec2::new(ndc.x, -ndc.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(mouse: &mut Mouse, buttons: &Input<MouseButton>, position: Vec2) {
+        mouse.normalised_device_coordinates = position;
+        update_buttons(mouse, Some(buttons));
+        update_drag(mouse);
+    }
+
+    #[test]
+    fn drag() {
+        let mut mouse = Mouse::default();
+        let mut buttons = Input::<MouseButton>::default();
+        let start = Vec2::new(-0.5, 0.25);
+
+        buttons.press(MouseButton::Left);
+        update(&mut mouse, &buttons, start);
+        assert_eq!(mouse.pressed, MouseButtonFlag::LEFT);
+        assert_eq!(mouse.just_pressed, MouseButtonFlag::LEFT);
+        assert_eq!(mouse.drag_start, Some(start));
+        assert!(!mouse.dragging());
+
+        // a second button keeps the start of the drag
+        buttons.clear();
+        buttons.press(MouseButton::Right);
+        update(&mut mouse, &buttons, Vec2::new(0.5, 0.0));
+        assert_eq!(
+            mouse.pressed,
+            MouseButtonFlag::LEFT | MouseButtonFlag::RIGHT
+        );
+        assert_eq!(mouse.just_pressed, MouseButtonFlag::RIGHT);
+        assert_eq!(mouse.drag_start, Some(start));
+        assert_eq!(mouse.drag_delta, Vec2::new(1.0, -0.25));
+        assert!(mouse.dragging());
+
+        buttons.clear();
+        buttons.release(MouseButton::Left);
+        buttons.release(MouseButton::Right);
+        update(&mut mouse, &buttons, Vec2::new(0.5, 0.0));
+        assert!(mouse.pressed.is_empty());
+        assert!(mouse.just_pressed.is_empty());
+        assert_eq!(
+            mouse.just_released,
+            MouseButtonFlag::LEFT | MouseButtonFlag::RIGHT
+        );
+        assert_eq!(mouse.drag_start, None);
+        assert_eq!(mouse.drag_delta, Vec2::ZERO);
+        assert!(!mouse.dragging());
     }
 }
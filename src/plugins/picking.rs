@@ -0,0 +1,413 @@
+//! Picking meshes with the cursor.
+//!
+//! [PickingPlugin] casts a world-space [Ray] from the cursor through every camera
+//! rendering to the window the cursor is in and intersects it with the meshes of
+//! the entities marked [PickableMesh]. The nearest hit of each camera is reported
+//! with [MeshHovered] and [MeshClicked] events, with the surface point, normal and
+//! texture coordinates.
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+        primitives::Aabb,
+    },
+    utils::HashMap,
+};
+
+use super::mouse::{viewport_ndc, Mouse};
+
+/// Marker component of the entities intersected by the cursor rays.
+///
+/// The entity also needs a [Handle<Mesh>] and a [GlobalTransform].
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct PickableMesh;
+
+/// A half-line in world space
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    /// Start of the ray
+    pub origin: Vec3,
+    /// Normalised direction of the ray
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Unprojects normalised device coordinates of a camera to a ray starting at
+    /// the near plane.
+    ///
+    /// Returns [None] if the camera projection cannot be inverted.
+    pub fn from_ndc(
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        ndc: Vec2,
+    ) -> Option<Self> {
+        let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
+        // bevy uses reversed z, the near plane is at 1 and far away points at 0
+        let near = ndc_to_world.project_point3(ndc.extend(1.0));
+        let far = ndc_to_world.project_point3(ndc.extend(0.5));
+        let direction = (far - near).try_normalize()?;
+        near.is_finite().then_some(Ray {
+            origin: near,
+            direction,
+        })
+    }
+
+    /// Point at a distance along the ray
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Transforms the ray with a matrix, the direction is not normalised.
+    fn transform(&self, matrix: &Mat4) -> Self {
+        Ray {
+            origin: matrix.transform_point3(self.origin),
+            direction: matrix.transform_vector3(self.direction),
+        }
+    }
+}
+
+/// Intersection of a [Ray] with a mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Hit point in world space
+    pub point: Vec3,
+    /// Surface normal in world space. Interpolated from the vertex normals if the mesh has them.
+    pub normal: Vec3,
+    /// Interpolated texture coordinates, if the mesh has them
+    pub uv: Option<Vec2>,
+    /// Distance from the ray origin
+    pub distance: f32,
+    /// Index of the hit triangle
+    pub triangle: usize,
+}
+
+/// The cursor ray of a camera hits a mesh.
+///
+/// Sent every frame the hit changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshHovered {
+    /// Camera the ray was cast from
+    pub camera: Entity,
+    /// Hit entity
+    pub entity: Entity,
+    /// Hit
+    pub hit: RayHit,
+}
+
+/// A mouse button was pressed while the cursor ray of a camera hits a mesh.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshClicked {
+    /// Camera the ray was cast from
+    pub camera: Entity,
+    /// Hit entity
+    pub entity: Entity,
+    /// Hit
+    pub hit: RayHit,
+    /// Pressed button
+    pub button: MouseButton,
+}
+
+/// Cursor rays of the cameras rendering to the window the cursor is in, by camera
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CursorRays(pub HashMap<Entity, Ray>);
+
+/// [Plugin] that casts the cursor rays and sends the mesh picking events.
+///
+/// Needs the [MousePlugin](super::mouse::MousePlugin).
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorRays>()
+            .add_event::<MeshHovered>()
+            .add_event::<MeshClicked>()
+            .add_system(cursor_rays)
+            .add_system(pick_meshes.after(cursor_rays));
+    }
+}
+
+fn cursor_rays(
+    mouse: Res<Mouse>,
+    windows: Option<Res<Windows>>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform)>,
+    mut rays: ResMut<CursorRays>,
+) {
+    rays.0.clear();
+
+    let (window, position) = match (mouse.window, windows) {
+        (Some(window_id), Some(windows)) => match (windows.get(window_id), mouse.get(window_id)) {
+            (Some(window), Some(state)) => (window, state.position),
+            _ => return,
+        },
+        _ => return,
+    };
+
+    for (entity, camera, camera_transform) in cameras.iter() {
+        if !camera.is_active
+            || !matches!(camera.target, RenderTarget::Window(id) if id == window.id())
+        {
+            continue;
+        }
+        if let Some(ray) = viewport_ndc(camera, window, position)
+            .and_then(|ndc| Ray::from_ndc(camera, camera_transform, ndc))
+        {
+            rays.0.insert(entity, ray);
+        }
+    }
+}
+
+fn pick_meshes(
+    rays: Res<CursorRays>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    meshes: Res<Assets<Mesh>>,
+    pickables: Query<(Entity, &Handle<Mesh>, &GlobalTransform, Option<&Aabb>), With<PickableMesh>>,
+    mut hovered: Local<HashMap<Entity, (Entity, RayHit)>>,
+    mut hovered_events: EventWriter<MeshHovered>,
+    mut clicked_events: EventWriter<MeshClicked>,
+) {
+    let mut current = HashMap::default();
+
+    for (&camera, ray) in rays.0.iter() {
+        let nearest = pickables
+            .iter()
+            .filter_map(|(entity, mesh, transform, aabb)| {
+                let mesh = meshes.get(mesh)?;
+                ray_mesh_intersection(ray, mesh, &transform.compute_matrix(), aabb)
+                    .map(|hit| (entity, hit))
+            })
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance));
+
+        let (entity, hit) = match nearest {
+            Some(nearest) => nearest,
+            None => continue,
+        };
+
+        if hovered.get(&camera) != Some(&(entity, hit)) {
+            hovered_events.send(MeshHovered {
+                camera,
+                entity,
+                hit,
+            });
+        }
+        for &button in mouse_buttons.get_just_pressed() {
+            clicked_events.send(MeshClicked {
+                camera,
+                entity,
+                hit,
+                button,
+            });
+        }
+
+        current.insert(camera, (entity, hit));
+    }
+
+    *hovered = current;
+}
+
+/// Intersects a ray with a triangle list mesh transformed by `transform`.
+///
+/// If given, the ray is tested against the [Aabb] of the mesh before its triangles.
+/// Returns the nearest hit in front of the ray origin.
+pub fn ray_mesh_intersection(
+    ray: &Ray,
+    mesh: &Mesh,
+    transform: &Mat4,
+    aabb: Option<&Aabb>,
+) -> Option<RayHit> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION)? {
+        VertexAttributeValues::Float32x3(positions) => positions,
+        _ => return None,
+    };
+    let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+        _ => None,
+    };
+    let uvs = match mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+        Some(VertexAttributeValues::Float32x2(uvs)) => Some(uvs),
+        _ => None,
+    };
+
+    // intersect in mesh space, distances are the same fraction of the direction
+    let local_ray = ray.transform(&transform.inverse());
+    if let Some(aabb) = aabb {
+        if !ray_aabb(&local_ray, aabb.min().into(), aabb.max().into()) {
+            return None;
+        }
+    }
+
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.iter().map(|i| *i as usize).collect(),
+        Some(Indices::U32(indices)) => indices.iter().map(|i| *i as usize).collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    let mut nearest: Option<(usize, [usize; 3], f32, Vec2)> = None;
+    for (triangle, vertices) in indices.chunks_exact(3).enumerate() {
+        let vertices = [vertices[0], vertices[1], vertices[2]];
+        if vertices.iter().any(|i| *i >= positions.len()) {
+            continue;
+        }
+        let [a, b, c] = vertices.map(|i| Vec3::from(positions[i]));
+        if let Some((t, barycentric)) = ray_triangle(&local_ray, a, b, c) {
+            if nearest.map_or(true, |(_, _, nearest_t, _)| t < nearest_t) {
+                nearest = Some((triangle, vertices, t, barycentric));
+            }
+        }
+    }
+
+    let (triangle, vertices, t, barycentric) = nearest?;
+    let weights = Vec3::new(
+        1.0 - barycentric.x - barycentric.y,
+        barycentric.x,
+        barycentric.y,
+    );
+    let [a, b, c] = vertices.map(|i| Vec3::from(positions[i]));
+
+    let local_normal = match normals {
+        Some(normals) => {
+            let [na, nb, nc] = vertices.map(|i| Vec3::from(normals[i]));
+            na * weights.x + nb * weights.y + nc * weights.z
+        }
+        None => (b - a).cross(c - a),
+    };
+    let uv = uvs.map(|uvs| {
+        let [ua, ub, uc] = vertices.map(|i| Vec2::from(uvs[i]));
+        ua * weights.x + ub * weights.y + uc * weights.z
+    });
+
+    let point = transform.transform_point3(local_ray.at(t));
+    // normals transform with the inverse transpose
+    let normal = transform
+        .inverse()
+        .transpose()
+        .transform_vector3(local_normal)
+        .normalize_or_zero();
+
+    Some(RayHit {
+        point,
+        normal,
+        uv,
+        distance: point.distance(ray.origin),
+        triangle,
+    })
+}
+
+/// Möller–Trumbore ray triangle intersection, both faces.
+///
+/// Returns the distance along the ray, in units of its direction, and the
+/// barycentric coordinates of `b` and `c`.
+fn ray_triangle(ray: &Ray, a: Vec3, b: Vec3, c: Vec3) -> Option<(f32, Vec2)> {
+    let edge_1 = b - a;
+    let edge_2 = c - a;
+    let p = ray.direction.cross(edge_2);
+    let determinant = edge_1.dot(p);
+    if determinant.abs() < f32::EPSILON {
+        // parallel
+        return None;
+    }
+    let inverse_determinant = 1.0 / determinant;
+
+    let s = ray.origin - a;
+    let u = s.dot(p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge_1);
+    let v = ray.direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge_2.dot(q) * inverse_determinant;
+    (t > 0.0).then_some((t, Vec2::new(u, v)))
+}
+
+/// Slab test of a ray against an axis aligned box.
+fn ray_aabb(ray: &Ray, min: Vec3, max: Vec3) -> bool {
+    let (mut t_enter, mut t_exit) = (0.0f32, f32::INFINITY);
+    for axis in 0..3 {
+        if ray.direction[axis] == 0.0 {
+            // parallel to the slab, the ray is always or never between its planes
+            if ray.origin[axis] < min[axis] || ray.origin[axis] > max[axis] {
+                return false;
+            }
+            continue;
+        }
+        let t_1 = (min[axis] - ray.origin[axis]) / ray.direction[axis];
+        let t_2 = (max[axis] - ray.origin[axis]) / ray.direction[axis];
+        t_enter = t_enter.max(t_1.min(t_2));
+        t_exit = t_exit.min(t_1.max(t_2));
+    }
+    t_enter <= t_exit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_hits_triangle() {
+        let ray = Ray {
+            origin: Vec3::new(0.25, 0.25, 5.0),
+            direction: Vec3::NEG_Z,
+        };
+        let (t, barycentric) =
+            ray_triangle(&ray, Vec3::ZERO, Vec3::X, Vec3::Y).expect("ray hits the triangle");
+        assert!((t - 5.0).abs() < 1e-5);
+        assert!((barycentric - Vec2::new(0.25, 0.25)).length() < 1e-5);
+
+        let behind = Ray {
+            direction: Vec3::Z,
+            ..ray
+        };
+        assert_eq!(ray_triangle(&behind, Vec3::ZERO, Vec3::X, Vec3::Y), None);
+    }
+
+    #[test]
+    fn axis_parallel_ray_on_a_face() {
+        let (min, max) = (Vec3::splat(-1.0), Vec3::splat(1.0));
+        let along_x = Ray {
+            origin: Vec3::new(-3.0, 1.0, -1.0),
+            direction: Vec3::X,
+        };
+        assert!(ray_aabb(&along_x, min, max));
+        let along_y = Ray {
+            origin: Vec3::new(-1.0, -3.0, 1.0),
+            direction: Vec3::Y,
+        };
+        assert!(ray_aabb(&along_y, min, max));
+
+        let outside = Ray {
+            origin: Vec3::new(-3.0, 1.5, 0.0),
+            direction: Vec3::X,
+        };
+        assert!(!ray_aabb(&outside, min, max));
+    }
+
+    #[test]
+    fn ray_hits_transformed_cube() {
+        let mesh = Mesh::from(shape::Cube { size: 1.0 });
+        let transform = Mat4::from_translation(Vec3::new(0.0, 0.5, 0.0));
+        let ray = Ray {
+            origin: Vec3::new(0.0, 0.5, 5.0),
+            direction: Vec3::NEG_Z,
+        };
+
+        let hit = ray_mesh_intersection(&ray, &mesh, &transform, None).expect("ray hits the cube");
+        assert!((hit.point - Vec3::new(0.0, 0.5, 0.5)).length() < 1e-5);
+        assert!((hit.normal - Vec3::Z).length() < 1e-5);
+        assert!((hit.distance - 4.5).abs() < 1e-5);
+        assert!(hit.uv.is_some());
+
+        let miss = Ray {
+            origin: Vec3::new(2.0, 0.5, 5.0),
+            ..ray
+        };
+        assert_eq!(ray_mesh_intersection(&miss, &mesh, &transform, None), None);
+    }
+}
@@ -7,8 +7,11 @@
 
 use super::{
     bundle::PixelBufferBundle,
-    frame::{Frame, FrameEditExtension, GetFrame},
-    pixel_buffer::{create_image, Fill, PixelBuffer, PixelBufferSize},
+    frame::{Frame, FrameEditExtension, FrameError, GetFrame},
+    fullscreen::{FullscreenOrder, PixelBufferFullscreen},
+    mesh::{BufferMaterial, BufferMesh, PixelBufferMesh},
+    pixel_buffer::{try_create_image, Fill, FillKind, PixelBuffer, PixelBufferSize, ResizePolicy},
+    ui::PixelBufferNode,
 };
 use bevy::{ecs::system::EntityCommands, prelude::*, sprite::Anchor};
 
@@ -24,6 +27,31 @@ pub enum RenderConfig {
         /// Different from [SpriteBundle] because [SpriteBundle] some extra fields that are not customisable.
         sprite_bundle: CustomSpriteBundle,
     },
+    /// Set up a 3D mesh textured with the pixel buffer.
+    ///
+    /// No camera is spawned, the mesh is rendered by the cameras of the 3D scene.
+    Mesh {
+        /// Custom mesh bundle parameters.
+        mesh_bundle: CustomMeshBundle,
+    },
+    /// Set up a UI image node and an optional 2D camera.
+    ///
+    /// The node can be parented to other UI nodes. With [Fill::node] the
+    /// buffer fills the size the layout gives to the node.
+    ///
+    /// [Fill::window] is replaced by [Fill::node], keeping its mode, so the buffer
+    /// fills the node instead of the whole window.
+    Ui {
+        /// Spawn a 2D camera
+        spawn_camera: bool,
+        /// Custom image bundle parameters.
+        image_bundle: CustomImageBundle,
+    },
+    /// Draw the buffer directly to a window in a dedicated render graph node.
+    ///
+    /// No sprite or camera is needed. The buffer is scaled by the largest integer factor
+    /// that fits the window and letterboxed. See [fullscreen](crate::fullscreen).
+    Fullscreen(PixelBufferFullscreen),
 }
 
 /// Customisable params for the sprite bundle that will be rendered by [RenderConfig].
@@ -51,6 +79,34 @@ pub struct CustomSprite {
     pub anchor: Anchor,
 }
 
+/// Customisable params for the mesh that will be rendered by [RenderConfig].
+///
+/// See [PbrBundle] for docs. The mesh and material assets are created
+/// from [CustomMeshBundle::mesh] and [CustomMeshBundle::material].
+#[derive(Clone, Debug, Default)]
+#[allow(missing_docs)]
+pub struct CustomMeshBundle {
+    pub mesh: BufferMesh,
+    pub material: BufferMaterial,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// Customisable params for the UI image that will be rendered by [RenderConfig].
+///
+/// See [ImageBundle] for docs. [Style::size] is overwritten with the size of the
+/// buffer unless the buffer fills the node, then an [Val::Auto] size is 100% of the
+/// parent.
+#[derive(Clone, Debug, Default)]
+#[allow(missing_docs)]
+pub struct CustomImageBundle {
+    pub style: Style,
+    pub color: Color,
+    pub visibility: Visibility,
+}
+
 impl Default for RenderConfig {
     fn default() -> Self {
         Self::sprite()
@@ -75,6 +131,84 @@ impl RenderConfig {
             sprite_bundle: Default::default(),
         }
     }
+
+    /// Set up an unlit quad with the aspect ratio of the buffer.
+    ///
+    /// The quad is `height` world units tall.
+    pub fn quad(height: f32) -> Self {
+        Self::Mesh {
+            mesh_bundle: CustomMeshBundle {
+                mesh: BufferMesh::Quad { height },
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Set up a user supplied mesh with an unlit material.
+    pub fn mesh(mesh: Handle<Mesh>) -> Self {
+        Self::Mesh {
+            mesh_bundle: CustomMeshBundle {
+                mesh: BufferMesh::Custom(mesh),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Set up a UI node and a 2D camera
+    pub fn ui_and_camera() -> Self {
+        Self::Ui {
+            spawn_camera: true,
+            image_bundle: Default::default(),
+        }
+    }
+
+    /// Set up a UI node.
+    ///
+    /// A camera also needs to be spawned to see the UI.
+    pub fn ui() -> Self {
+        Self::Ui {
+            spawn_camera: false,
+            image_bundle: Default::default(),
+        }
+    }
+
+    /// Draw the buffer over everything in the primary window, the scene stays visible
+    /// around it.
+    pub fn fullscreen() -> Self {
+        Self::Fullscreen(Default::default())
+    }
+
+    /// Draw the buffer behind the cameras of the primary window.
+    ///
+    /// The cameras must not clear the window to see the buffer.
+    pub fn fullscreen_background() -> Self {
+        Self::Fullscreen(PixelBufferFullscreen {
+            order: FullscreenOrder::Background,
+            letterbox_color: Some(Color::BLACK),
+            ..Default::default()
+        })
+    }
+
+    /// Use a lit [StandardMaterial] for a [RenderConfig::Mesh] configuration.
+    ///
+    /// The base color texture of the material will be replaced by the pixel buffer.
+    /// Other configurations are returned unchanged.
+    pub fn with_material(mut self, material: StandardMaterial) -> Self {
+        if let Self::Mesh { mesh_bundle } = &mut self {
+            mesh_bundle.material = BufferMaterial::Standard(material);
+        }
+        self
+    }
+
+    /// Set the transform of a [RenderConfig::Mesh] configuration.
+    ///
+    /// Other configurations are returned unchanged.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        if let Self::Mesh { mesh_bundle } = &mut self {
+            mesh_bundle.transform = transform;
+        }
+        self
+    }
 }
 
 /// Helper type to allow easy [RenderConfig] conversions inside the [PixelBufferBuilder].
@@ -137,6 +271,8 @@ pub struct PixelBufferBuilder {
     pub size: PixelBufferSize,
     /// Fill behaviour
     pub fill: Fill,
+    /// Content behaviour on resize
+    pub resize_policy: ResizePolicy,
     /// Set up rendering
     pub render: Option<RenderConfig>,
 }
@@ -146,6 +282,7 @@ impl Default for PixelBufferBuilder {
         Self {
             size: Default::default(),
             fill: Default::default(),
+            resize_policy: Default::default(),
             render: Some(RenderConfig::sprite_and_camera()),
         }
     }
@@ -170,7 +307,13 @@ impl PixelBufferBuilder {
         self
     }
 
-    /// Set wether and how to render the pixel buffer using the bevy 2D renderer.
+    /// Set what happens to the content of the buffer when it's resized.
+    pub fn with_resize_policy(mut self, resize_policy: ResizePolicy) -> Self {
+        self.resize_policy = resize_policy;
+        self
+    }
+
+    /// Set wether and how to render the pixel buffer using the bevy 2D or 3D renderer.
     ///
     /// The type [RenderConfigBuilder] allows for some ergnomics to build the [RenderConfig].
     pub fn with_render(mut self, render: impl Into<RenderConfigBuilder>) -> Self {
@@ -179,24 +322,75 @@ impl PixelBufferBuilder {
     }
 
     /// Spawns a new entity and inserts a pixel buffer with the builder's configuration to it.
+    ///
+    /// # Panics
+    /// If the image cannot be created, see [PixelBufferBuilder::try_spawn].
     pub fn spawn<'w, 's, 'a>(
         self,
         commands: &'a mut Commands<'w, 's>,
         images: &'a mut Assets<Image>,
     ) -> PixelBufferCommands<'w, 's, 'a> {
+        self.try_spawn(commands, images)
+            .expect("create pixel buffer")
+    }
+
+    /// Same as [PixelBufferBuilder::spawn] but returns an error if the image cannot be
+    /// created, for example if the size is 0. No entity is spawned then.
+    pub fn try_spawn<'w, 's, 'a>(
+        self,
+        commands: &'a mut Commands<'w, 's>,
+        images: &'a mut Assets<Image>,
+    ) -> Result<PixelBufferCommands<'w, 's, 'a>, FrameError> {
+        let image = try_create_image(self.size.size.into())?;
         let entity = commands.spawn();
-        create_pixel_buffer(entity, images, self.size, self.fill, self.render)
+        Ok(create_pixel_buffer(
+            entity,
+            images,
+            image,
+            self.pixel_buffer(),
+            self.render,
+        ))
     }
 
     /// Inserts a new pixel buffer with the builder's configuration into an existing entity.
+    ///
+    /// # Panics
+    /// If the image cannot be created, see [PixelBufferBuilder::try_insert].
     pub fn insert<'w, 's, 'a>(
         self,
         commands: &'a mut Commands<'w, 's>,
         images: &'a mut Assets<Image>,
         entity: Entity,
     ) -> PixelBufferCommands<'w, 's, 'a> {
+        self.try_insert(commands, images, entity)
+            .expect("create pixel buffer")
+    }
+
+    /// Same as [PixelBufferBuilder::insert] but returns an error if the image cannot be
+    /// created, for example if the size is 0. Nothing is inserted then.
+    pub fn try_insert<'w, 's, 'a>(
+        self,
+        commands: &'a mut Commands<'w, 's>,
+        images: &'a mut Assets<Image>,
+        entity: Entity,
+    ) -> Result<PixelBufferCommands<'w, 's, 'a>, FrameError> {
+        let image = try_create_image(self.size.size.into())?;
         let entity = commands.entity(entity);
-        create_pixel_buffer(entity, images, self.size, self.fill, self.render)
+        Ok(create_pixel_buffer(
+            entity,
+            images,
+            image,
+            self.pixel_buffer(),
+            self.render,
+        ))
+    }
+
+    fn pixel_buffer(&self) -> PixelBuffer {
+        PixelBuffer {
+            size: self.size,
+            fill: self.fill,
+            resize_policy: self.resize_policy,
+        }
     }
 
     /// Returns a system that spawns a pixel buffer with the builder's configuration.
@@ -204,7 +398,8 @@ impl PixelBufferBuilder {
     /// This allows to avoid writing a system just to create a [PixelBufferBuilder]
     /// and [spawn](PixelBufferBuilder::spawn) a pixel buffer.
     ///
-    /// The system is the equivalent as the one shown in the [PixelBufferBuilder] example.
+    /// The system is the equivalent as the one shown in the [PixelBufferBuilder] example,
+    /// but logs an error instead of panicking if the pixel buffer cannot be created.
     ///
     /// # Example
     ///
@@ -234,7 +429,9 @@ impl PixelBufferBuilder {
     /// ```
     pub fn setup(self) -> impl FnMut(Commands, ResMut<Assets<Image>>) {
         move |mut commands, mut images| {
-            self.clone().spawn(&mut commands, &mut images);
+            if let Err(err) = self.clone().try_spawn(&mut commands, &mut images) {
+                error!("Could not create pixel buffer: {}", err);
+            }
         }
     }
 }
@@ -242,11 +439,12 @@ impl PixelBufferBuilder {
 fn create_pixel_buffer<'w, 's, 'a>(
     mut entity: EntityCommands<'w, 's, 'a>,
     images: &'a mut Assets<Image>,
-    size: PixelBufferSize,
-    fill: Fill,
+    image: Image,
+    mut pixel_buffer: PixelBuffer,
     render: Option<RenderConfig>,
 ) -> PixelBufferCommands<'w, 's, 'a> {
-    let image = images.add(create_image(size.size.into()));
+    let size = pixel_buffer.size;
+    let image = images.add(image);
 
     if let Some(render) = render {
         match render {
@@ -279,11 +477,59 @@ fn create_pixel_buffer<'w, 's, 'a>(
                 };
                 entity.insert_bundle(sprite_bundle);
             }
+            RenderConfig::Mesh { mesh_bundle } => {
+                // The mesh and material assets are created by the plugin
+                // once the pixel buffer is in the world
+                entity
+                    .insert(PixelBufferMesh {
+                        mesh: mesh_bundle.mesh,
+                        material: mesh_bundle.material,
+                    })
+                    .insert_bundle((
+                        mesh_bundle.transform,
+                        mesh_bundle.global_transform,
+                        mesh_bundle.visibility,
+                        mesh_bundle.computed_visibility,
+                    ));
+            }
+            RenderConfig::Ui {
+                spawn_camera,
+                image_bundle,
+            } => {
+                // Spawn a 2D camera if needed
+                if spawn_camera {
+                    entity.commands().spawn_bundle(Camera2dBundle::default());
+                }
+
+                // inside the UI the area to fill is the node, not the whole window
+                if matches!(pixel_buffer.fill.kind, FillKind::Window(_)) {
+                    pixel_buffer.fill.kind = FillKind::Node;
+                }
+
+                let mut style = image_bundle.style;
+                if !matches!(pixel_buffer.fill.kind, FillKind::Node) {
+                    let screen_size = size.screen_size().as_vec2();
+                    style.size = Size::new(Val::Px(screen_size.x), Val::Px(screen_size.y));
+                }
+
+                entity
+                    .insert_bundle(ImageBundle {
+                        style,
+                        image: image.clone().into(),
+                        color: image_bundle.color.into(),
+                        visibility: image_bundle.visibility,
+                        ..Default::default()
+                    })
+                    .insert(PixelBufferNode);
+            }
+            RenderConfig::Fullscreen(fullscreen) => {
+                entity.insert(fullscreen);
+            }
         }
     }
 
     entity.insert_bundle(PixelBufferBundle {
-        pixel_buffer: PixelBuffer { size, fill },
+        pixel_buffer,
         image: image.clone(),
     });
 
@@ -325,9 +571,12 @@ pub fn pixel_buffer_setup(
 ) -> impl Fn(Commands, ResMut<Assets<Image>>) {
     let size = size.into();
     move |mut commands, mut images| {
-        PixelBufferBuilder::new()
+        if let Err(err) = PixelBufferBuilder::new()
             .with_size(size)
-            .spawn(&mut commands, &mut images);
+            .try_spawn(&mut commands, &mut images)
+        {
+            error!("Could not create pixel buffer: {}", err);
+        }
     }
 }
 
@@ -368,7 +617,7 @@ impl<'w, 's, 'a> PixelBufferCommands<'w, 's, 'a> {
 }
 
 impl<'w, 's, 'a> GetFrame for PixelBufferCommands<'w, 's, 'a> {
-    fn frame(&mut self) -> Frame<'_> {
-        Frame::extract(self.images, &self.image_handle)
+    fn try_frame(&mut self) -> Result<Frame<'_>, FrameError> {
+        Frame::try_extract(self.images, &self.image_handle)
     }
 }
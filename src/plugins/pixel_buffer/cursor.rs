@@ -0,0 +1,376 @@
+//! Mapping the cursor to pixels of sprite pixel buffers.
+//!
+//! [PixelCursorPlugin] finds the pixel under the cursor of every pixel buffer
+//! rendered with a [Sprite] and sends [PixelHovered], [PixelPressed] and
+//! [PixelDragged] events. The mapping accounts for the sprite transform,
+//! [Anchor](bevy::sprite::Anchor), flipping and [Sprite::custom_size], so the pixel
+//! size and stretching of the buffer.
+//!
+//! Buffers drawn directly to a window with a
+//! [PixelBufferFullscreen](crate::fullscreen::PixelBufferFullscreen) are mapped too,
+//! in whichever window they are drawn.
+
+use bevy::{
+    core_pipeline::core_2d::Camera2d, ecs::system::SystemParam, prelude::*,
+    render::camera::RenderTarget, utils::HashMap, window::WindowId,
+};
+
+use super::{
+    fullscreen::PixelBufferFullscreen,
+    pixel_buffer::{FillLayout, FillMode, PixelBuffer, PixelBufferSize},
+};
+use crate::plugins::mouse::viewport_ndc;
+
+/// The cursor moved to a new pixel of a pixel buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelHovered {
+    /// Pixel buffer entity
+    pub entity: Entity,
+    /// Pixel location, (0, 0) in the top left
+    pub pixel: UVec2,
+}
+
+/// A mouse button was pressed over a pixel of a pixel buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelPressed {
+    /// Pixel buffer entity
+    pub entity: Entity,
+    /// Pixel location, (0, 0) in the top left
+    pub pixel: UVec2,
+    /// Pressed button
+    pub button: MouseButton,
+}
+
+/// The cursor moved between pixels of a pixel buffer with a mouse button held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelDragged {
+    /// Pixel buffer entity
+    pub entity: Entity,
+    /// Pixel location in the previous frame
+    pub from: UVec2,
+    /// Pixel location, (0, 0) in the top left
+    pub to: UVec2,
+    /// Held button
+    pub button: MouseButton,
+}
+
+/// [Plugin] that sends the pixel cursor events.
+///
+/// Only pixel buffers rendered with a sprite by a 2D camera or drawn fullscreen are mapped.
+pub struct PixelCursorPlugin;
+
+impl Plugin for PixelCursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PixelHovered>()
+            .add_event::<PixelPressed>()
+            .add_event::<PixelDragged>()
+            .add_system_to_stage(CoreStage::PreUpdate, pixel_cursor);
+    }
+}
+
+/// Returns the pixel of a buffer of `buffer_size` rendered with `sprite` at a
+/// position in the local space of the sprite.
+///
+/// Returns [None] if the position is outside of the sprite or the sprite has no
+/// [Sprite::custom_size], which pixel buffer sprites always have.
+pub fn sprite_pixel(local: Vec2, sprite: &Sprite, buffer_size: UVec2) -> Option<UVec2> {
+    let size = sprite.custom_size?;
+    if buffer_size.x == 0 || buffer_size.y == 0 {
+        return None;
+    }
+
+    // sprite vertices are at `(quad - anchor) * size`, with quad in -0.5..0.5
+    let quad = local / size + sprite.anchor.as_vec();
+    // images have their origin in the top left
+    let mut uv = Vec2::new(quad.x + 0.5, 0.5 - quad.y);
+    if sprite.flip_x {
+        uv.x = 1.0 - uv.x;
+    }
+    if sprite.flip_y {
+        uv.y = 1.0 - uv.y;
+    }
+
+    if uv.cmplt(Vec2::ZERO).any() || uv.cmpge(Vec2::ONE).any() {
+        return None;
+    }
+
+    Some(
+        (uv * buffer_size.as_vec2())
+            .as_uvec2()
+            .min(buffer_size - UVec2::ONE),
+    )
+}
+
+/// Maps a position in physical pixels from the top left of a window to a pixel of a
+/// buffer drawn fullscreen in it with a [FillMode].
+///
+/// Returns [None] if the position is outside of the buffer or the buffer is empty.
+pub fn fullscreen_pixel(
+    size: &PixelBufferSize,
+    mode: FillMode,
+    window_size: Vec2,
+    position: Vec2,
+) -> Option<UVec2> {
+    // the layout is at least a pixel even for empty buffers
+    if size.size.x == 0 || size.size.y == 0 {
+        return None;
+    }
+    let layout = FillLayout::compute(size, mode, false, window_size);
+    let pixel = layout.area_to_buffer(position)?;
+    Some(pixel.as_uvec2().min(size.size - UVec2::ONE))
+}
+
+/// Unprojects normalised device coordinates of a camera to the world.
+fn ndc_to_world(camera: &Camera, camera_transform: &GlobalTransform, ndc: Vec2) -> Vec3 {
+    let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
+    ndc_to_world.project_point3(ndc.extend(0.0))
+}
+
+/// System parameter to find the pixel buffer pixels under a window position.
+///
+/// Used by [PixelCursorPlugin], it can be used to map other pointers such as touches.
+#[derive(SystemParam)]
+pub struct PixelPicker<'w, 's> {
+    windows: Res<'w, Windows>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<Camera2d>>,
+    buffers: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static PixelBuffer,
+            &'static Sprite,
+            &'static GlobalTransform,
+        ),
+    >,
+    fullscreen_buffers:
+        Query<'w, 's, (Entity, &'static PixelBuffer, &'static PixelBufferFullscreen)>,
+}
+
+impl<'w, 's> PixelPicker<'w, 's> {
+    /// Returns the pixel of every buffer under a position in a window, in logical
+    /// pixels from the bottom left as [Window::cursor_position].
+    pub fn pick(&self, window_id: WindowId, position: Vec2) -> HashMap<Entity, UVec2> {
+        let mut picked = HashMap::default();
+        let window = match self.windows.get(window_id) {
+            Some(window) => window,
+            None => return picked,
+        };
+
+        for (camera, camera_transform) in self.cameras.iter() {
+            if !matches!(camera.target, RenderTarget::Window(id) if id == window_id) {
+                continue;
+            }
+            let ndc = match viewport_ndc(camera, window, position) {
+                Some(ndc) => ndc,
+                None => continue,
+            };
+            let world = ndc_to_world(camera, camera_transform, ndc);
+
+            for (entity, pb, sprite, transform) in self.buffers.iter() {
+                if picked.contains_key(&entity) {
+                    continue;
+                }
+                let local = transform
+                    .compute_matrix()
+                    .inverse()
+                    .transform_point3(world)
+                    .truncate();
+                if let Some(pixel) = sprite_pixel(local, sprite, pb.size.size) {
+                    picked.insert(entity, pixel);
+                }
+            }
+        }
+
+        // fullscreen buffers are laid out in physical pixels from the top left
+        let window_size = Vec2::new(
+            window.physical_width() as f32,
+            window.physical_height() as f32,
+        );
+        let physical_position =
+            Vec2::new(position.x, window.height() - position.y) * window.scale_factor() as f32;
+        for (entity, pb, fullscreen) in self.fullscreen_buffers.iter() {
+            if fullscreen.window != window_id || picked.contains_key(&entity) {
+                continue;
+            }
+            if let Some(pixel) =
+                fullscreen_pixel(&pb.size, fullscreen.mode, window_size, physical_position)
+            {
+                picked.insert(entity, pixel);
+            }
+        }
+
+        picked
+    }
+
+    /// Returns the pixel of every buffer under the cursor of any window.
+    pub fn pick_cursor(&self) -> HashMap<Entity, UVec2> {
+        let mut picked = HashMap::default();
+        for window in self.windows.iter() {
+            if let Some(cursor) = window.cursor_position() {
+                picked.extend(self.pick(window.id(), cursor));
+            }
+        }
+        picked
+    }
+}
+
+fn pixel_cursor(
+    picker: PixelPicker,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut hovered: Local<HashMap<Entity, UVec2>>,
+    mut hovered_events: EventWriter<PixelHovered>,
+    mut pressed_events: EventWriter<PixelPressed>,
+    mut dragged_events: EventWriter<PixelDragged>,
+) {
+    let current = picker.pick_cursor();
+
+    for (&entity, &pixel) in current.iter() {
+        let previous = hovered.get(&entity).copied();
+
+        if previous != Some(pixel) {
+            hovered_events.send(PixelHovered { entity, pixel });
+        }
+
+        for &button in mouse_buttons.get_just_pressed() {
+            pressed_events.send(PixelPressed {
+                entity,
+                pixel,
+                button,
+            });
+        }
+
+        if let Some(from) = previous.filter(|from| *from != pixel) {
+            for &button in mouse_buttons.get_pressed() {
+                if !mouse_buttons.just_pressed(button) {
+                    dragged_events.send(PixelDragged {
+                        entity,
+                        from,
+                        to: pixel,
+                        button,
+                    });
+                }
+            }
+        }
+    }
+
+    *hovered = current;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::sprite::Anchor;
+
+    fn sprite(anchor: Anchor, flip_x: bool, flip_y: bool) -> Sprite {
+        Sprite {
+            custom_size: Some(Vec2::new(20.0, 10.0)),
+            anchor,
+            flip_x,
+            flip_y,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn centered_sprite() {
+        let buffer_size = UVec2::new(10, 5);
+        let sprite = sprite(Anchor::Center, false, false);
+
+        assert_eq!(
+            sprite_pixel(Vec2::new(-9.0, 4.0), &sprite, buffer_size),
+            Some(UVec2::new(0, 0))
+        );
+        assert_eq!(
+            sprite_pixel(Vec2::new(9.0, -4.0), &sprite, buffer_size),
+            Some(UVec2::new(9, 4))
+        );
+        assert_eq!(
+            sprite_pixel(Vec2::new(11.0, 0.0), &sprite, buffer_size),
+            None
+        );
+    }
+
+    #[test]
+    fn anchored_sprite() {
+        let buffer_size = UVec2::new(10, 5);
+        let sprite = sprite(Anchor::TopLeft, false, false);
+
+        assert_eq!(
+            sprite_pixel(Vec2::new(1.0, -1.0), &sprite, buffer_size),
+            Some(UVec2::new(0, 0))
+        );
+        assert_eq!(
+            sprite_pixel(Vec2::new(-1.0, -1.0), &sprite, buffer_size),
+            None
+        );
+    }
+
+    #[test]
+    fn fullscreen_buffer() {
+        let size = PixelBufferSize {
+            size: UVec2::new(10, 5),
+            pixel_size: UVec2::new(2, 2),
+        };
+        let window_size = Vec2::new(40.0, 20.0);
+
+        // scaled by 4 to fit the window
+        assert_eq!(
+            fullscreen_pixel(
+                &size,
+                FillMode::IntegerScale,
+                window_size,
+                Vec2::new(5.0, 1.0)
+            ),
+            Some(UVec2::new(1, 0))
+        );
+        assert_eq!(
+            fullscreen_pixel(
+                &size,
+                FillMode::IntegerScale,
+                window_size,
+                Vec2::new(39.0, 19.0)
+            ),
+            Some(UVec2::new(9, 4))
+        );
+        assert_eq!(
+            fullscreen_pixel(
+                &size,
+                FillMode::IntegerScale,
+                window_size,
+                Vec2::new(40.0, 0.0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_fullscreen_buffer() {
+        // a window smaller than a pixel, as when minimized
+        let size = PixelBufferSize {
+            size: UVec2::new(0, 5),
+            pixel_size: UVec2::new(2, 2),
+        };
+        for mode in [
+            FillMode::Resize,
+            FillMode::IntegerScale,
+            FillMode::AspectFit,
+        ] {
+            assert_eq!(
+                fullscreen_pixel(&size, mode, Vec2::new(1.0, 10.0), Vec2::new(0.5, 5.0)),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn flipped_sprite() {
+        let buffer_size = UVec2::new(10, 5);
+        let sprite = sprite(Anchor::Center, true, true);
+
+        assert_eq!(
+            sprite_pixel(Vec2::new(-9.0, 4.0), &sprite, buffer_size),
+            Some(UVec2::new(9, 4))
+        );
+    }
+}
@@ -1,7 +1,10 @@
 //! Frame and frame utility functions that helps to draw things on raw image data.
 
 use super::pixel::Pixel;
-use bevy::{prelude::*, render::render_resource::TextureUsages};
+use bevy::{
+    prelude::*,
+    render::render_resource::{TextureFormat, TextureUsages},
+};
 use rayon::prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 
 /// Helper structure to edit a pixel buffer
@@ -77,6 +80,93 @@ impl<'a> Frame<'a> {
         Ok(())
     }
 
+    /// Gets a pixel of the frame
+    pub fn get_pixel(&self, location: impl Into<UVec2>) -> Result<Pixel, FrameError> {
+        let location: UVec2 = location.into();
+        self.check_bounds(location)?;
+
+        let index = location.x + location.y * self.size.x;
+        Ok(self.pixels[index as usize])
+    }
+
+    /// Draws a line between two locations, both included.
+    ///
+    /// The parts of the line out of the frame are skipped.
+    pub fn line(&mut self, from: impl Into<IVec2>, to: impl Into<IVec2>, pixel: impl Into<Pixel>) {
+        let pixel = pixel.into();
+        for location in line_points(from.into(), to.into()) {
+            self.set_clipped(location, pixel);
+        }
+    }
+
+    /// Draws an axis aligned rectangle with opposite corners in two locations, both included.
+    ///
+    /// The parts of the rectangle out of the frame are skipped.
+    pub fn rect(
+        &mut self,
+        corner: impl Into<IVec2>,
+        opposite_corner: impl Into<IVec2>,
+        pixel: impl Into<Pixel>,
+        filled: bool,
+    ) {
+        let (a, b) = (corner.into(), opposite_corner.into());
+        let (min, max) = (a.min(b), a.max(b));
+        let pixel = pixel.into();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                if filled || x == min.x || x == max.x || y == min.y || y == max.y {
+                    self.set_clipped(IVec2::new(x, y), pixel);
+                }
+            }
+        }
+    }
+
+    /// Replaces the contiguous area (4-connected) of pixels equal to the one in
+    /// `location` with `pixel`.
+    pub fn flood_fill(
+        &mut self,
+        location: impl Into<UVec2>,
+        pixel: impl Into<Pixel>,
+    ) -> FrameResult {
+        let location: UVec2 = location.into();
+        let target = self.get_pixel(location)?;
+        let pixel = pixel.into();
+        if target == pixel {
+            return Ok(());
+        }
+
+        let mut stack = vec![location];
+        while let Some(location) = stack.pop() {
+            let index = (location.x + location.y * self.size.x) as usize;
+            if self.pixels[index] != target {
+                continue;
+            }
+            self.pixels[index] = pixel;
+
+            if location.x > 0 {
+                stack.push(location - UVec2::X);
+            }
+            if location.x + 1 < self.size.x {
+                stack.push(location + UVec2::X);
+            }
+            if location.y > 0 {
+                stack.push(location - UVec2::Y);
+            }
+            if location.y + 1 < self.size.y {
+                stack.push(location + UVec2::Y);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets a pixel if the location is inside the frame
+    fn set_clipped(&mut self, location: IVec2, pixel: Pixel) {
+        if location.cmpge(IVec2::ZERO).all() {
+            let _ = self.set(location.as_uvec2(), pixel);
+        }
+    }
+
     fn check_bounds(&self, location: UVec2) -> FrameResult {
         if location.x >= self.size.x || location.y >= self.size.y {
             Err(FrameError::LocationOutOfBounds {
@@ -89,6 +179,34 @@ impl<'a> Frame<'a> {
     }
 }
 
+/// Returns the locations of a line between two locations, both included, using
+/// Bresenham's algorithm.
+pub fn line_points(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut error = delta.x - delta.y;
+    let mut current = from;
+    let mut points = Vec::with_capacity(delta.max_element() as usize + 1);
+
+    loop {
+        points.push(current);
+        if current == to {
+            break;
+        }
+        let error2 = error * 2;
+        if error2 > -delta.y {
+            error -= delta.y;
+            current.x += step.x;
+        }
+        if error2 < delta.x {
+            error += delta.x;
+            current.y += step.y;
+        }
+    }
+
+    points
+}
+
 /// Result type for some methods of [Frame]
 pub type FrameResult = Result<(), FrameError>;
 
@@ -106,28 +224,83 @@ pub enum FrameError {
         /// frame size
         size: UVec2,
     },
+    /// The image is not in the assets
+    #[error("image not found in the assets")]
+    MissingImage,
+    /// The image format is not [Pixel::FORMAT]
+    #[error(
+        "wrong image format (format: {format:?}, expected: {:?})",
+        Pixel::FORMAT
+    )]
+    WrongFormat {
+        /// image format
+        format: TextureFormat,
+    },
+    /// The image has 0 width or height
+    #[error("image size is 0 in some dimension (size: {size:?})")]
+    ZeroSize {
+        /// image size
+        size: UVec2,
+    },
+    /// The image lacks some texture usages needed by pixel buffers
+    #[error("image is missing texture usages (missing: {missing:?})")]
+    MissingUsages {
+        /// missing usages
+        missing: TextureUsages,
+    },
+    /// There is no pixel buffer when one was expected
+    #[error("no pixel buffer found")]
+    NoPixelBuffer,
+    /// There is more than one pixel buffer when only one was expected
+    #[error("more than one pixel buffer found")]
+    MultiplePixelBuffers,
 }
 
 impl<'a> Frame<'a> {
     /// Builds a frame from a bevy image
+    ///
+    /// # Panics
+    /// If the image format is not [Pixel::FORMAT], see [Frame::try_get].
     pub fn get(image: &'a mut Image) -> Self {
-        debug_assert_eq!(image.texture_descriptor.format, Pixel::FORMAT);
+        Self::try_get(image).expect("frame from image")
+    }
+
+    /// Builds a frame from a bevy image.
+    ///
+    /// Returns [FrameError::WrongFormat] if the image format is not [Pixel::FORMAT].
+    pub fn try_get(image: &'a mut Image) -> Result<Self, FrameError> {
+        let format = image.texture_descriptor.format;
+        if format != Pixel::FORMAT {
+            return Err(FrameError::WrongFormat { format });
+        }
         debug_assert!(image
             .texture_descriptor
             .usage
             .contains(TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST));
         let size = image.size().as_uvec2();
         let pixels = bytemuck::cast_slice_mut(&mut image.data);
-        Self { pixels, size }
+        Ok(Self { pixels, size })
     }
 
     /// Builds a frame by extracting a bevy image from the assets.
+    ///
+    /// # Panics
+    /// If the image is not in the assets or has the wrong format, see [Frame::try_extract].
     pub fn extract(images: &'a mut Assets<Image>, image_handle: &Handle<Image>) -> Self {
-        Self::get(
-            images
-                .get_mut(image_handle)
-                .expect("image when building frame"),
-        )
+        Self::try_extract(images, image_handle).expect("image when building frame")
+    }
+
+    /// Builds a frame by extracting a bevy image from the assets.
+    ///
+    /// Returns [FrameError::MissingImage] if the image is not in the assets.
+    pub fn try_extract(
+        images: &'a mut Assets<Image>,
+        image_handle: &Handle<Image>,
+    ) -> Result<Self, FrameError> {
+        let image = images
+            .get_mut(image_handle)
+            .ok_or(FrameError::MissingImage)?;
+        Self::try_get(image)
     }
 
     /// Constructs a frame from raw parts
@@ -151,13 +324,21 @@ impl<'a> Frame<'a> {
 /// Convenience trait to get a [Frame]
 pub trait GetFrame {
     /// Get a frame to mutate a pixel buffer
-    fn frame(&mut self) -> Frame<'_>;
+    fn try_frame(&mut self) -> Result<Frame<'_>, FrameError>;
+
+    /// Get a frame to mutate a pixel buffer
+    ///
+    /// # Panics
+    /// If the frame cannot be built, see [GetFrame::try_frame].
+    fn frame(&mut self) -> Frame<'_> {
+        self.try_frame().expect("frame")
+    }
 }
 
 impl GetFrame for Image {
     #[inline(always)]
-    fn frame(&mut self) -> Frame<'_> {
-        Frame::get(self)
+    fn try_frame(&mut self) -> Result<Frame<'_>, FrameError> {
+        Frame::try_get(self)
     }
 }
 
@@ -167,6 +348,11 @@ pub trait GetFrameFromHandle: AsImageHandle {
     fn frame<'a>(&self, images: &'a mut Assets<Image>) -> Frame<'a> {
         Frame::extract(images, self.as_image_hande())
     }
+
+    /// Get a frame to mutate a pixel buffer, without panicking if the image is missing
+    fn try_frame<'a>(&self, images: &'a mut Assets<Image>) -> Result<Frame<'a>, FrameError> {
+        Frame::try_extract(images, self.as_image_hande())
+    }
 }
 
 impl<T: AsImageHandle> GetFrameFromHandle for T {}
@@ -177,6 +363,11 @@ pub trait GetFrameFromImages: AsMut<Assets<Image>> {
     fn frame(&mut self, image_handle: impl AsImageHandle) -> Frame<'_> {
         Frame::extract(self.as_mut(), image_handle.as_image_hande())
     }
+
+    /// Get a frame to mutate a pixel buffer, without panicking if the image is missing
+    fn try_frame(&mut self, image_handle: impl AsImageHandle) -> Result<Frame<'_>, FrameError> {
+        Frame::try_extract(self.as_mut(), image_handle.as_image_hande())
+    }
 }
 
 impl<T: AsMut<Assets<Image>>> GetFrameFromImages for T {}
@@ -207,6 +398,12 @@ pub trait FrameEditExtension: GetFrame {
     fn edit_frame(&mut self, f: impl Fn(&mut Frame)) {
         f(&mut self.frame())
     }
+
+    /// Runs a given closure to modify the buffer if the frame can be built.
+    fn try_edit_frame(&mut self, f: impl Fn(&mut Frame)) -> FrameResult {
+        f(&mut self.try_frame()?);
+        Ok(())
+    }
 }
 
 impl<T: GetFrame> FrameEditExtension for T {}
@@ -0,0 +1,331 @@
+//! Presenting a pixel buffer directly to a window.
+//!
+//! A dedicated render graph node draws the buffer on the window swapchain with a
+//! fullscreen triangle, so no sprite or camera is needed. The buffer is fitted in
+//! the window following a [FillMode], by default scaled by the largest integer
+//! factor that fits and centered, leaving letterbox bars around it.
+//!
+//! Usually set up with [RenderConfig::Fullscreen](crate::builder::RenderConfig::Fullscreen), but
+//! the [PixelBufferFullscreen] component can also be inserted manually next to a
+//! [PixelBufferBundle](crate::bundle::PixelBufferBundle).
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssets,
+        render_graph::{self, RenderGraph},
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ExtractedWindows,
+        Extract, RenderApp, RenderStage,
+    },
+    utils::HashSet,
+    window::WindowId,
+};
+
+use super::pixel_buffer::{FillLayout, FillMode, PixelBuffer, PixelBufferSize};
+
+/// Name of the render graph node that draws the buffers behind the cameras.
+pub const FULLSCREEN_BACKGROUND_NODE: &str = "pixel_buffer_fullscreen_background";
+/// Name of the render graph node that draws the buffers over the cameras.
+pub const FULLSCREEN_OVERLAY_NODE: &str = "pixel_buffer_fullscreen_overlay";
+
+/// Component to present a pixel buffer directly to a window.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct PixelBufferFullscreen {
+    /// Window to draw in
+    pub window: WindowId,
+    /// When to draw relative to the cameras rendering to the same window
+    pub order: FullscreenOrder,
+    /// How the buffer is fitted in the window.
+    ///
+    /// [FillMode::Resize] draws the buffer with its [PixelBufferSize::pixel_size],
+    /// it does not resize it. Add a [Fill](crate::pixel_buffer::Fill) for that.
+    pub mode: FillMode,
+    /// If set, the window is cleared with this color before drawing the buffer,
+    /// which also paints the letterbox bars.
+    ///
+    /// The window is cleared once by the first buffer with a color, so other buffers
+    /// drawn in the same order are kept. With [FullscreenOrder::Overlay] this also
+    /// clears the scene of the cameras.
+    pub letterbox_color: Option<Color>,
+}
+
+impl Default for PixelBufferFullscreen {
+    fn default() -> Self {
+        Self {
+            window: WindowId::primary(),
+            order: FullscreenOrder::Overlay,
+            mode: FillMode::IntegerScale,
+            // keep the scene around the buffer
+            letterbox_color: None,
+        }
+    }
+}
+
+/// When a fullscreen pixel buffer is drawn relative to the cameras.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FullscreenOrder {
+    /// Before the cameras.
+    ///
+    /// The cameras rendering to the window must not clear it to see the buffer, use
+    /// [ClearColorConfig::None](bevy::core_pipeline::clear_color::ClearColorConfig::None).
+    /// Windows without any camera are cleared by bevy after this, so use
+    /// [FullscreenOrder::Overlay] for them.
+    Background,
+    /// After the cameras, on top of the scene.
+    Overlay,
+}
+
+/// Registers the fullscreen nodes in the render app.
+pub(crate) fn build_render_app(app: &mut App) {
+    if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app
+            .init_resource::<FullscreenPipeline>()
+            .init_resource::<ExtractedFullscreenBuffers>()
+            .init_resource::<FullscreenQueue>()
+            .add_system_to_stage(RenderStage::Extract, extract_fullscreen_buffers)
+            .add_system_to_stage(RenderStage::Queue, queue_fullscreen_buffers);
+
+        let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
+        render_graph.add_node(
+            FULLSCREEN_BACKGROUND_NODE,
+            FullscreenNode {
+                order: FullscreenOrder::Background,
+            },
+        );
+        render_graph.add_node(
+            FULLSCREEN_OVERLAY_NODE,
+            FullscreenNode {
+                order: FullscreenOrder::Overlay,
+            },
+        );
+        render_graph
+            .add_node_edge(
+                FULLSCREEN_BACKGROUND_NODE,
+                bevy::render::main_graph::node::CAMERA_DRIVER,
+            )
+            .expect("extend bevy render graph with fullscreen background node");
+        render_graph
+            .add_node_edge(
+                bevy::render::main_graph::node::CAMERA_DRIVER,
+                FULLSCREEN_OVERLAY_NODE,
+            )
+            .expect("extend bevy render graph with fullscreen overlay node");
+    }
+}
+
+#[derive(Resource)]
+struct FullscreenPipeline {
+    pipeline_id: CachedRenderPipelineId,
+    bind_group_layout: BindGroupLayout,
+}
+
+impl FromWorld for FullscreenPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let device = world.resource::<RenderDevice>();
+        let asset_server = world.resource::<AssetServer>();
+        let shader = asset_server.load("shaders/pixel_buffer/fullscreen.wgsl");
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("pixel_buffer_fullscreen_bind_group_layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let mut pipeline_cache = world.resource_mut::<PipelineCache>();
+        let pipeline_id = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+            label: Some("pixel_buffer_fullscreen_pipeline".into()),
+            layout: Some(vec![bind_group_layout.clone()]),
+            vertex: VertexState {
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: "vs_main".into(),
+                buffers: vec![],
+            },
+            fragment: Some(FragmentState {
+                shader,
+                shader_defs: vec![],
+                entry_point: "fs_main".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: TextureFormat::bevy_default(),
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+        });
+
+        FullscreenPipeline {
+            pipeline_id,
+            bind_group_layout,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct ExtractedFullscreenBuffers(Vec<(Handle<Image>, PixelBufferSize, PixelBufferFullscreen)>);
+
+fn extract_fullscreen_buffers(
+    mut extracted: ResMut<ExtractedFullscreenBuffers>,
+    buffers: Extract<Query<(&Handle<Image>, &PixelBuffer, &PixelBufferFullscreen)>>,
+) {
+    extracted.0.clear();
+    for (image_handle, pb, fullscreen) in buffers.iter() {
+        extracted
+            .0
+            .push((image_handle.clone_weak(), pb.size, *fullscreen));
+    }
+}
+
+struct FullscreenDraw {
+    bind_group: BindGroup,
+    window: WindowId,
+    order: FullscreenOrder,
+    letterbox_color: Option<Color>,
+    offset: UVec2,
+    size: UVec2,
+}
+
+#[derive(Resource, Default)]
+struct FullscreenQueue(Vec<FullscreenDraw>);
+
+fn queue_fullscreen_buffers(
+    mut queue: ResMut<FullscreenQueue>,
+    extracted: Res<ExtractedFullscreenBuffers>,
+    render_device: Res<RenderDevice>,
+    pipeline: Res<FullscreenPipeline>,
+    images: Res<RenderAssets<Image>>,
+    windows: Res<ExtractedWindows>,
+) {
+    queue.0.clear();
+    for (image_handle, buffer_size, fullscreen) in extracted.0.iter() {
+        let (gpu_image, window) = match (images.get(image_handle), windows.get(&fullscreen.window))
+        {
+            (Some(gpu_image), Some(window)) => (gpu_image, window),
+            _ => continue,
+        };
+
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("pixel_buffer_fullscreen_bind_group"),
+            layout: &pipeline.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&gpu_image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&gpu_image.sampler),
+                },
+            ],
+        });
+
+        let window_size = UVec2::new(window.physical_width, window.physical_height);
+        if window_size.x == 0 || window_size.y == 0 {
+            // minimized
+            continue;
+        }
+        let layout =
+            FillLayout::compute(buffer_size, fullscreen.mode, false, window_size.as_vec2());
+        // the viewport has to be inside the window
+        let offset = layout.offset.round().max(Vec2::ZERO).as_uvec2();
+        let size = layout.size.round().as_uvec2().min(window_size - offset);
+
+        queue.0.push(FullscreenDraw {
+            bind_group,
+            window: fullscreen.window,
+            order: fullscreen.order,
+            letterbox_color: fullscreen.letterbox_color,
+            offset,
+            size,
+        });
+    }
+}
+
+struct FullscreenNode {
+    order: FullscreenOrder,
+}
+
+impl render_graph::Node for FullscreenNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let pipeline = world.resource::<FullscreenPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let windows = world.resource::<ExtractedWindows>();
+        let queue = world.resource::<FullscreenQueue>();
+
+        let render_pipeline = match pipeline_cache.get_render_pipeline(pipeline.pipeline_id) {
+            Some(render_pipeline) => render_pipeline,
+            // still loading
+            None => return Ok(()),
+        };
+
+        let mut cleared = HashSet::default();
+        for draw in queue.0.iter().filter(|draw| draw.order == self.order) {
+            let swap_chain_texture = match windows
+                .get(&draw.window)
+                .and_then(|window| window.swap_chain_texture.as_ref())
+            {
+                Some(swap_chain_texture) => swap_chain_texture,
+                None => continue,
+            };
+
+            // clearing again would erase the buffers drawn before
+            let load = match draw.letterbox_color {
+                Some(color) if cleared.insert(draw.window) => LoadOp::Clear(color.into()),
+                _ => LoadOp::Load,
+            };
+
+            let mut pass =
+                render_context
+                    .command_encoder
+                    .begin_render_pass(&RenderPassDescriptor {
+                        label: Some("pixel_buffer_fullscreen_pass"),
+                        color_attachments: &[Some(RenderPassColorAttachment {
+                            view: swap_chain_texture,
+                            resolve_target: None,
+                            ops: Operations { load, store: true },
+                        })],
+                        depth_stencil_attachment: None,
+                    });
+
+            pass.set_pipeline(render_pipeline);
+            pass.set_bind_group(0, &draw.bind_group, &[]);
+            pass.set_viewport(
+                draw.offset.x as f32,
+                draw.offset.y as f32,
+                draw.size.x as f32,
+                draw.size.y as f32,
+                0.0,
+                1.0,
+            );
+            pass.draw(0..3, 0..1);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+//! Rendering a pixel buffer on a 3D mesh.
+//!
+//! The image of the pixel buffer is used as the base color texture of a
+//! [StandardMaterial], either on a quad that keeps the aspect ratio of the
+//! buffer or on a user supplied mesh.
+//!
+//! Usually set up with [RenderConfig::Mesh](crate::builder::RenderConfig::Mesh), but
+//! the [PixelBufferMesh] component can also be inserted manually next to a
+//! [PixelBufferBundle](crate::bundle::PixelBufferBundle).
+
+use bevy::prelude::*;
+
+use super::pixel_buffer::PixelBuffer;
+
+/// Component describing how to render a pixel buffer on a mesh.
+///
+/// When added, the [PixelBufferPlugin](crate::pixel_buffer::PixelBufferPlugin) creates the
+/// mesh and material assets and inserts their handles in the entity.
+#[derive(Component, Clone, Debug, Default)]
+pub struct PixelBufferMesh {
+    /// Mesh the image is applied to
+    pub mesh: BufferMesh,
+    /// Material used to render the image
+    pub material: BufferMaterial,
+}
+
+/// Mesh used to render a pixel buffer
+#[derive(Clone, Debug)]
+pub enum BufferMesh {
+    /// A quad facing +Z with the aspect ratio of the buffer.
+    ///
+    /// The quad is rebuilt when the [PixelBufferSize](crate::pixel_buffer::PixelBufferSize) changes.
+    Quad {
+        /// Height of the quad in world units. The width is derived from the buffer aspect ratio.
+        height: f32,
+    },
+    /// A user supplied mesh. It needs UVs for the image to be mapped.
+    ///
+    /// The mesh is never modified.
+    Custom(Handle<Mesh>),
+}
+
+impl Default for BufferMesh {
+    fn default() -> Self {
+        Self::Quad { height: 1.0 }
+    }
+}
+
+/// Material used to render a pixel buffer
+#[derive(Clone, Debug)]
+pub enum BufferMaterial {
+    /// Unlit material, the pixels are shown with their exact color.
+    Unlit,
+    /// Lit material. The given material is used as a template and its
+    /// [StandardMaterial::base_color_texture] is replaced by the pixel buffer image.
+    Standard(StandardMaterial),
+}
+
+impl Default for BufferMaterial {
+    fn default() -> Self {
+        Self::Unlit
+    }
+}
+
+impl BufferMaterial {
+    fn create(&self, image: Handle<Image>) -> StandardMaterial {
+        match self {
+            BufferMaterial::Unlit => StandardMaterial {
+                base_color_texture: Some(image),
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..Default::default()
+            },
+            BufferMaterial::Standard(template) => StandardMaterial {
+                base_color_texture: Some(image),
+                ..template.clone()
+            },
+        }
+    }
+}
+
+/// Builds a quad with the given height and the aspect ratio of the buffer.
+fn buffer_quad(pb: &PixelBuffer, height: f32) -> Mesh {
+    let screen_size = pb.size.screen_size().as_vec2();
+    let aspect = if screen_size.y > 0.0 {
+        screen_size.x / screen_size.y
+    } else {
+        1.0
+    };
+    Mesh::from(shape::Quad::new(Vec2::new(height * aspect, height)))
+}
+
+/// Creates the mesh and material of newly added [PixelBufferMesh] components.
+#[allow(clippy::type_complexity)]
+pub(crate) fn setup_mesh(
+    mut commands: Commands,
+    pixel_buffer: Query<
+        (Entity, &PixelBuffer, &Handle<Image>, &PixelBufferMesh),
+        Added<PixelBufferMesh>,
+    >,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    materials: Option<ResMut<Assets<StandardMaterial>>>,
+) {
+    let (mut meshes, mut materials) = match (meshes, materials) {
+        (Some(meshes), Some(materials)) => (meshes, materials),
+        _ => return,
+    };
+
+    for (entity, pb, image, buffer_mesh) in pixel_buffer.iter() {
+        let mesh = match &buffer_mesh.mesh {
+            BufferMesh::Quad { height } => meshes.add(buffer_quad(pb, *height)),
+            BufferMesh::Custom(mesh) => mesh.clone(),
+        };
+        let material = materials.add(buffer_mesh.material.create(image.clone()));
+
+        commands.entity(entity).insert(mesh).insert(material);
+    }
+}
+
+/// Keeps the aspect ratio of the [BufferMesh::Quad] in sync with the buffer size.
+#[allow(clippy::type_complexity)]
+pub(crate) fn mesh_quad_size(
+    pixel_buffer: Query<(&PixelBuffer, &PixelBufferMesh, &Handle<Mesh>), Changed<PixelBuffer>>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+) {
+    let mut meshes = match meshes {
+        Some(meshes) => meshes,
+        None => return,
+    };
+
+    for (pb, buffer_mesh, mesh) in pixel_buffer.iter() {
+        if let BufferMesh::Quad { height } = buffer_mesh.mesh {
+            if let Some(mesh) = meshes.get_mut(mesh) {
+                info!("Resizing quad to: {:?}", pb.size);
+                *mesh = buffer_quad(pb, height);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::pixel_buffer::{bundle::PixelBufferBundle, pixel_buffer::PixelBufferSize};
+    use bevy::render::mesh::VertexAttributeValues;
+
+    /// Width and height of a quad
+    fn quad_size(mesh: &Mesh) -> Vec2 {
+        let positions = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => positions,
+            _ => panic!("mesh without positions"),
+        };
+        let (min, max) = positions.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), position| {
+                let position = Vec2::new(position[0], position[1]);
+                (min.min(position), max.max(position))
+            },
+        );
+        max - min
+    }
+
+    #[test]
+    fn quad_keeps_the_screen_aspect_ratio() {
+        let pb = PixelBuffer {
+            size: PixelBufferSize::size(UVec2::new(30, 10)),
+            ..Default::default()
+        };
+        assert_eq!(quad_size(&buffer_quad(&pb, 1.5)), Vec2::new(4.5, 1.5));
+
+        // the pixel size counts, 16x8 pixels of 1x2 are square on screen
+        let pb = PixelBuffer {
+            size: PixelBufferSize {
+                size: UVec2::new(16, 8),
+                pixel_size: UVec2::new(1, 2),
+            },
+            ..Default::default()
+        };
+        assert_eq!(quad_size(&buffer_quad(&pb, 2.0)), Vec2::new(2.0, 2.0));
+
+        let pb = PixelBuffer {
+            size: PixelBufferSize::size(UVec2::new(4, 0)),
+            ..Default::default()
+        };
+        assert_eq!(quad_size(&buffer_quad(&pb, 1.0)), Vec2::ONE);
+    }
+
+    #[test]
+    fn do_resize_quad() {
+        let mut app = App::new();
+
+        app.add_plugin(bevy::asset::AssetPlugin).add_asset::<Mesh>();
+
+        app.add_system(mesh_quad_size);
+
+        let mut meshes = app.world.resource_mut::<Assets<Mesh>>();
+        let mesh = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(3.0, 1.0))));
+
+        let pb_id = app
+            .world
+            .spawn()
+            .insert_bundle(PixelBufferBundle {
+                pixel_buffer: PixelBuffer {
+                    size: PixelBufferSize::size(UVec2::new(4, 4)),
+                    ..Default::default()
+                },
+                image: Default::default(),
+            })
+            .insert(PixelBufferMesh {
+                mesh: BufferMesh::Quad { height: 1.0 },
+                ..Default::default()
+            })
+            .insert(mesh.clone())
+            .id();
+
+        app.update();
+
+        let meshes = app.world.resource::<Assets<Mesh>>();
+        assert_eq!(quad_size(meshes.get(&mesh).unwrap()), Vec2::new(1.0, 1.0));
+
+        app.world.get_mut::<PixelBuffer>(pb_id).unwrap().size.size = UVec2::new(8, 2);
+        app.update();
+
+        let meshes = app.world.resource::<Assets<Mesh>>();
+        assert_eq!(quad_size(meshes.get(&mesh).unwrap()), Vec2::new(4.0, 1.0));
+    }
+}
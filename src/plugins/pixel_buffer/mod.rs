@@ -1,7 +1,12 @@
 pub mod builder;
 pub mod bundle;
 pub mod compute_shader;
+pub mod cursor;
 pub mod frame;
+pub mod fullscreen;
+pub mod mesh;
+pub mod paint;
 pub mod pixel;
 pub mod pixel_buffer;
 pub mod queries;
+pub mod ui;
@@ -0,0 +1,352 @@
+//! Interactive painting on pixel buffers.
+//!
+//! [PixelPaintPlugin] applies mouse and touch strokes to the [Frame] of the
+//! pixel buffer under the pointer with the tool selected in the [PixelPaint]
+//! resource. Strokes are interpolated between frames so fast drags leave no gaps.
+//!
+//! Only pixel buffers that can be picked by [PixelPicker], that is rendered with
+//! a sprite or drawn fullscreen, can be painted.
+
+use bevy::{prelude::*, utils::HashMap, window::WindowId};
+
+use super::{
+    cursor::PixelPicker,
+    frame::{line_points, Frame, GetFrameFromImages},
+    pixel::Pixel,
+    pixel_buffer::PixelBuffer,
+};
+use crate::events::Touches;
+
+/// Painting tool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaintTool {
+    /// Paints the [PixelPaint::color] with the [PixelPaint::brush].
+    Brush,
+    /// Erases to transparent with the [PixelPaint::brush].
+    Eraser,
+    /// Draws a line from where the stroke starts to where it ends.
+    Line,
+    /// Draws a rectangle with corners where the stroke starts and ends.
+    Rectangle {
+        /// Fill the rectangle or only draw the outline
+        filled: bool,
+    },
+    /// Picks the [PixelPaint::color] from the pressed pixel.
+    Eyedropper,
+    /// Fills the contiguous area of the pressed pixel.
+    Bucket,
+}
+
+/// Brush of the [PaintTool::Brush] and [PaintTool::Eraser] tools
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Brush {
+    /// Diameter in pixels
+    pub size: f32,
+    /// Fraction of the radius painted at full opacity, the rest fades out.
+    ///
+    /// `1.0` is a hard brush, `0.0` fades from the center.
+    pub hardness: f32,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            size: 1.0,
+            hardness: 1.0,
+        }
+    }
+}
+
+/// Resource with the paint configuration
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PixelPaint {
+    /// Selected tool
+    pub tool: PaintTool,
+    /// Paint color
+    pub color: Color,
+    /// Brush for the brush and eraser tools
+    pub brush: Brush,
+    /// Mouse button that paints
+    pub button: MouseButton,
+    /// Paint with touches
+    pub touch: bool,
+    /// Painting enabled
+    pub enabled: bool,
+}
+
+impl Default for PixelPaint {
+    fn default() -> Self {
+        Self {
+            tool: PaintTool::Brush,
+            color: Color::WHITE,
+            brush: Default::default(),
+            button: MouseButton::Left,
+            touch: true,
+            enabled: true,
+        }
+    }
+}
+
+/// [Plugin] that paints on pixel buffers with the mouse and touches.
+///
+/// Needs the [PixelCursorPlugin](crate::cursor::PixelCursorPlugin) cameras setup,
+/// a 2D camera rendering the sprite of the buffer. Touches are read from the
+/// [Touches] of the [TouchPlugin](crate::events::TouchPlugin), if added.
+pub struct PixelPaintPlugin;
+
+impl Plugin for PixelPaintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PixelPaint>().add_system_to_stage(
+            CoreStage::PreUpdate,
+            paint.after(crate::events::touch_screen_input_system),
+        );
+    }
+}
+
+/// A pointer that can paint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Pointer {
+    Mouse,
+    Touch(u64),
+}
+
+/// A stroke in progress
+#[derive(Debug, Clone, Copy)]
+struct Stroke {
+    entity: Entity,
+    start: UVec2,
+    last: UVec2,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn paint(
+    mut paint: ResMut<PixelPaint>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    touches: Option<Res<Touches>>,
+    picker: PixelPicker,
+    buffers: Query<(&Handle<Image>, Option<&GlobalTransform>), With<PixelBuffer>>,
+    mut images: ResMut<Assets<Image>>,
+    mut strokes: Local<HashMap<Pointer, Stroke>>,
+) {
+    if !paint.enabled {
+        strokes.clear();
+        return;
+    }
+
+    // (pointer, held, picked pixels)
+    let mut pointers = Vec::new();
+    if mouse_buttons.pressed(paint.button) || mouse_buttons.just_released(paint.button) {
+        pointers.push((
+            Pointer::Mouse,
+            mouse_buttons.pressed(paint.button),
+            picker.pick_cursor(),
+        ));
+    }
+    if let Some(touches) = touches.filter(|_| paint.touch) {
+        for touch in touches.iter() {
+            pointers.push((
+                Pointer::Touch(touch.id()),
+                true,
+                picker.pick(WindowId::primary(), touch.position()),
+            ));
+        }
+        for touch in touches.iter_just_released() {
+            pointers.push((
+                Pointer::Touch(touch.id()),
+                false,
+                picker.pick(WindowId::primary(), touch.position()),
+            ));
+        }
+    }
+
+    for (pointer, held, picked) in pointers {
+        match strokes.get(&pointer).copied() {
+            // new stroke
+            None if held => {
+                // overlapping buffers paint the topmost one
+                let topmost = picked
+                    .iter()
+                    .filter_map(|(&entity, &pixel)| {
+                        let (image, transform) = buffers.get(entity).ok()?;
+                        let z = transform.map_or(0.0, |t| t.translation().z);
+                        Some((z, entity, image, pixel))
+                    })
+                    .max_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+                if let Some((_, entity, image, pixel)) = topmost {
+                    // the image can be missing or being resized
+                    let mut frame = match images.try_frame(image) {
+                        Ok(frame) => frame,
+                        Err(_) => continue,
+                    };
+                    begin_stroke(&mut paint, &mut frame, pixel);
+                    strokes.insert(
+                        pointer,
+                        Stroke {
+                            entity,
+                            start: pixel,
+                            last: pixel,
+                        },
+                    );
+                }
+            }
+            None => {}
+            Some(mut stroke) => {
+                // the buffer or its image was removed or resized mid stroke
+                let frame = match buffers.get(stroke.entity) {
+                    Ok((image, _)) => images.try_frame(image).ok(),
+                    Err(_) => None,
+                };
+                let mut frame = match frame {
+                    Some(frame) => frame,
+                    None => {
+                        strokes.remove(&pointer);
+                        continue;
+                    }
+                };
+
+                // the pointer can leave the buffer, keep the last pixel
+                if let Some(&pixel) = picked.get(&stroke.entity) {
+                    if pixel != stroke.last {
+                        continue_stroke(&paint, &mut frame, stroke.last, pixel);
+                        stroke.last = pixel;
+                    }
+                }
+
+                if held {
+                    strokes.insert(pointer, stroke);
+                } else {
+                    end_stroke(&paint, &mut frame, stroke.start, stroke.last);
+                    strokes.remove(&pointer);
+                }
+            }
+        }
+    }
+}
+
+fn begin_stroke(paint: &mut PixelPaint, frame: &mut Frame, pixel: UVec2) {
+    match paint.tool {
+        PaintTool::Brush => stamp(frame, pixel.as_vec2(), &paint.brush, paint.color.into()),
+        PaintTool::Eraser => stamp(frame, pixel.as_vec2(), &paint.brush, Pixel::TRANSPARENT),
+        PaintTool::Eyedropper => {
+            if let Ok(picked) = frame.get_pixel(pixel) {
+                paint.color = picked.as_color();
+            }
+        }
+        PaintTool::Bucket => {
+            let _ = frame.flood_fill(pixel, paint.color);
+        }
+        PaintTool::Line | PaintTool::Rectangle { .. } => {}
+    }
+}
+
+fn continue_stroke(paint: &PixelPaint, frame: &mut Frame, from: UVec2, to: UVec2) {
+    let pixel = match paint.tool {
+        PaintTool::Brush => paint.color.into(),
+        PaintTool::Eraser => Pixel::TRANSPARENT,
+        _ => return,
+    };
+
+    // stamp along the segment, the first point was stamped by the previous frame
+    let spacing = (paint.brush.size * 0.25).max(1.0);
+    let mut last_stamp = from.as_vec2();
+    for point in line_points(from.as_ivec2(), to.as_ivec2())
+        .into_iter()
+        .skip(1)
+    {
+        let point = point.as_vec2();
+        if point.distance(last_stamp) >= spacing || point == to.as_vec2() {
+            stamp(frame, point, &paint.brush, pixel);
+            last_stamp = point;
+        }
+    }
+}
+
+fn end_stroke(paint: &PixelPaint, frame: &mut Frame, start: UVec2, end: UVec2) {
+    match paint.tool {
+        PaintTool::Line => frame.line(start.as_ivec2(), end.as_ivec2(), paint.color),
+        PaintTool::Rectangle { filled } => {
+            frame.rect(start.as_ivec2(), end.as_ivec2(), paint.color, filled)
+        }
+        _ => {}
+    }
+}
+
+/// Stamps the brush centered in a pixel, blending `pixel` over the frame.
+fn stamp(frame: &mut Frame, center: Vec2, brush: &Brush, pixel: Pixel) {
+    let radius = (brush.size * 0.5).max(0.5);
+    let hard_radius = radius * brush.hardness.clamp(0.0, 1.0);
+    let size = frame.size().as_ivec2();
+
+    let min = (center - radius).floor().as_ivec2().max(IVec2::ZERO);
+    let max = (center + radius).ceil().as_ivec2().min(size - IVec2::ONE);
+
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let location = IVec2::new(x, y);
+            let distance = location.as_vec2().distance(center);
+            if distance >= radius {
+                continue;
+            }
+
+            let coverage = if distance <= hard_radius {
+                1.0
+            } else {
+                1.0 - (distance - hard_radius) / (radius - hard_radius)
+            };
+
+            let location = location.as_uvec2();
+            if let Ok(current) = frame.get_pixel(location) {
+                let _ = frame.set(location, blend(current, pixel, coverage));
+            }
+        }
+    }
+}
+
+/// Blends `src` over `dst` with a coverage factor.
+fn blend(dst: Pixel, src: Pixel, coverage: f32) -> Pixel {
+    let mix = |d: u8, s: u8| (d as f32 + (s as f32 - d as f32) * coverage).round() as u8;
+    Pixel {
+        r: mix(dst.r, src.r),
+        g: mix(dst.g, src.g),
+        b: mix(dst.b, src.b),
+        a: mix(dst.a, src.a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolated_stroke_has_no_gaps() {
+        let mut pixels = vec![Pixel::TRANSPARENT; 10 * 10];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(10, 10));
+        let paint = PixelPaint::default();
+
+        continue_stroke(&paint, &mut frame, UVec2::new(0, 0), UVec2::new(9, 0));
+
+        for x in 1..10 {
+            assert_eq!(frame.get_pixel((x, 0)).unwrap(), Pixel::WHITE);
+        }
+        assert_eq!(frame.get_pixel((0, 1)).unwrap(), Pixel::TRANSPARENT);
+    }
+
+    #[test]
+    fn bucket_fills_contiguous_area() {
+        let mut pixels = vec![Pixel::TRANSPARENT; 5 * 5];
+        let mut frame = Frame::from_raw_parts(&mut pixels, UVec2::new(5, 5));
+        // wall splitting the frame in two
+        frame.line((2, 0), (2, 4), Pixel::BLACK);
+
+        let mut paint = PixelPaint {
+            tool: PaintTool::Bucket,
+            color: Color::RED,
+            ..Default::default()
+        };
+        begin_stroke(&mut paint, &mut frame, UVec2::new(0, 0));
+
+        assert_eq!(frame.get_pixel((1, 4)).unwrap(), Pixel::from(Color::RED));
+        assert_eq!(frame.get_pixel((2, 2)).unwrap(), Pixel::BLACK);
+        assert_eq!(frame.get_pixel((3, 0)).unwrap(), Pixel::TRANSPARENT);
+    }
+}
@@ -1,4 +1,6 @@
 use bevy::{
+    asset::HandleId,
+    ecs::system::SystemParam,
     prelude::*,
     render::{
         render_resource::{Extent3d, TextureDescriptor, TextureDimension, TextureUsages},
@@ -7,7 +9,13 @@ use bevy::{
     window::WindowId,
 };
 
-use super::pixel::Pixel;
+use super::{
+    frame::FrameError,
+    fullscreen,
+    mesh::{mesh_quad_size, setup_mesh},
+    pixel::Pixel,
+    ui::node_size,
+};
 
 /// Component defining a pixel buffer.
 ///
@@ -19,6 +27,49 @@ pub struct PixelBuffer {
     pub size: PixelBufferSize,
     /// Fill mode
     pub fill: Fill,
+    /// What happens to the image content when the size changes
+    pub resize_policy: ResizePolicy,
+}
+
+/// What happens to the content of a pixel buffer when it's resized.
+///
+/// The new pixels are transparent, except when rescaling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizePolicy {
+    /// Discard the content
+    Clear,
+    /// Keep the content anchored to the top left corner
+    KeepTopLeft,
+    /// Keep the content centered
+    KeepCentered,
+    /// Rescale the content to the new size
+    Rescale(ResizeFilter),
+}
+
+impl Default for ResizePolicy {
+    fn default() -> Self {
+        Self::KeepTopLeft
+    }
+}
+
+/// Filter used by [ResizePolicy::Rescale]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeFilter {
+    /// Take the nearest pixel
+    Nearest,
+    /// Interpolate the 4 nearest pixels
+    Bilinear,
+}
+
+/// Event sent when the image of a pixel buffer is resized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelBufferResized {
+    /// Pixel buffer entity
+    pub entity: Entity,
+    /// Previous size
+    pub old: UVec2,
+    /// New size
+    pub new: UVec2,
 }
 
 /// Size of a pixel buffer.
@@ -30,10 +81,11 @@ pub struct PixelBufferSize {
     pub pixel_size: UVec2,
 }
 
-/// Fill behaviour of the pixel buffer, resizing it automatically
+/// Fill behaviour of the pixel buffer, resizing or scaling it automatically
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Fill {
     pub(crate) kind: FillKind,
+    pub(crate) mode: FillMode,
     pub(crate) stretch: bool,
     pub(crate) multiple: u32,
 }
@@ -47,12 +99,101 @@ pub enum FillKind {
     Window(WindowId),
     /// Fill a customs size
     Custom(Vec2),
+    /// Fill the viewport of a camera.
+    ///
+    /// Without a custom viewport this is the size of the camera render target.
+    Camera(Entity),
+    /// Fill an image, usually the render target of a camera.
+    ///
+    /// Only the id is kept, the image must be kept alive by a [Handle] elsewhere.
+    Image(HandleId),
+    /// Fill the computed size of the UI node the buffer is rendered in.
+    ///
+    /// Only has effect with a [PixelBufferNode](crate::ui::PixelBufferNode),
+    /// the node [Style] decides the size of the buffer.
+    Node,
+}
+
+/// How the buffer is fitted in the fill area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillMode {
+    /// Resize the buffer to cover the area, keeping [PixelBufferSize::pixel_size].
+    Resize,
+    /// Keep the buffer size and scale it by the largest integer factor that fits
+    /// in the area (pixel perfect), centered with letterbox bars.
+    ///
+    /// If the buffer does not fit at all, it is shrunk as in [FillMode::AspectFit].
+    IntegerScale,
+    /// Keep the buffer size and scale it by the largest factor that fits in the
+    /// area preserving the aspect ratio, centered with letterbox bars.
+    AspectFit,
+}
+
+impl Default for FillMode {
+    fn default() -> Self {
+        Self::Resize
+    }
+}
+
+/// Placement of a pixel buffer inside its fill area.
+///
+/// Kept up to date by the [PixelBufferPlugin] for buffers with a [Fill],
+/// it can be used to map positions in the area to buffer pixels.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct FillLayout {
+    /// Size of the fill area
+    pub area: Vec2,
+    /// Offset from the top left corner of the area to the top left corner of the
+    /// buffer. The space around the buffer are the letterbox bars.
+    pub offset: Vec2,
+    /// Size the buffer takes up in the area
+    pub size: Vec2,
+    /// Size of each buffer pixel in the area
+    pub scale: Vec2,
+}
+
+impl FillLayout {
+    /// Fits a buffer of the given size in an area.
+    pub fn compute(size: &PixelBufferSize, mode: FillMode, stretch: bool, area: Vec2) -> Self {
+        let buffer = size.size.as_vec2().max(Vec2::ONE);
+        let fit = (area / buffer).min_element();
+
+        let scaled = if stretch {
+            area
+        } else {
+            match mode {
+                FillMode::Resize => size.screen_size().as_vec2(),
+                FillMode::IntegerScale if fit >= 1.0 => buffer * fit.floor(),
+                FillMode::IntegerScale | FillMode::AspectFit => buffer * fit,
+            }
+        };
+
+        Self {
+            area,
+            offset: (area - scaled) / 2.0,
+            size: scaled,
+            scale: scaled / buffer,
+        }
+    }
+
+    /// Maps a position in the area, with (0, 0) in the top left, to a position in
+    /// the buffer in pixels.
+    ///
+    /// Returns [None] if the position falls outside of the buffer, in the letterbox bars.
+    pub fn area_to_buffer(&self, position: Vec2) -> Option<Vec2> {
+        let local = position - self.offset;
+        if local.cmplt(Vec2::ZERO).any() || local.cmpge(self.size).any() {
+            return None;
+        }
+        Some(local / self.scale)
+    }
 }
 
 impl Default for Fill {
     fn default() -> Self {
         Self {
             kind: FillKind::None,
+            mode: FillMode::Resize,
             stretch: false,
             multiple: 1,
         }
@@ -69,6 +210,9 @@ impl Fill {
     }
 
     /// Fill the primary window
+    ///
+    /// Buffers rendered with [RenderConfig::Ui](crate::builder::RenderConfig::Ui)
+    /// fill their node instead, see [Fill::node].
     pub fn window() -> Self {
         Self {
             kind: FillKind::Window(WindowId::primary()),
@@ -84,6 +228,40 @@ impl Fill {
         }
     }
 
+    /// Fill the viewport of a camera
+    pub fn camera(camera: Entity) -> Self {
+        Self {
+            kind: FillKind::Camera(camera),
+            ..Default::default()
+        }
+    }
+
+    /// Fill an image
+    ///
+    /// The fill does not keep the image loaded, a strong [Handle] must be kept
+    /// elsewhere, such as the [RenderTarget](bevy::render::camera::RenderTarget) of
+    /// a camera. Once the image is removed the buffer keeps its last size.
+    pub fn image(image: &Handle<Image>) -> Self {
+        Self {
+            kind: FillKind::Image(image.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Fill the UI node the buffer is rendered in
+    pub fn node() -> Self {
+        Self {
+            kind: FillKind::Node,
+            ..Default::default()
+        }
+    }
+
+    /// Set how the buffer is fitted in the area. See [FillMode].
+    pub fn with_mode(mut self, mode: FillMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
     /// Wether to stretch the rendering sprite to fill the area
     pub fn with_stretch(mut self, stretch: bool) -> Self {
         self.stretch = stretch;
@@ -221,7 +399,18 @@ impl From<UVec2> for CreateImageParams {
 /// - If the size is 0 in either dimension.
 /// - If the usages do not contain [TextureUsages::TEXTURE_BINDING],  [TextureUsages::COPY_DST] and [TextureUsages::STORAGE_BINDING].
 ///
+/// See [try_create_image] for a version that does not panic.
 pub fn create_image(params: CreateImageParams) -> Image {
+    try_create_image(params).expect("create pixel buffer image")
+}
+
+/// Same as [create_image] but returns an error instead of panicking.
+///
+/// # Errors
+/// - [FrameError::ZeroSize] if the size is 0 in either dimension.
+/// - [FrameError::MissingUsages] if the usages do not contain [TextureUsages::TEXTURE_BINDING],
+/// [TextureUsages::COPY_DST] and [TextureUsages::STORAGE_BINDING].
+pub fn try_create_image(params: CreateImageParams) -> Result<Image, FrameError> {
     let CreateImageParams {
         size,
         label,
@@ -229,11 +418,16 @@ pub fn create_image(params: CreateImageParams) -> Image {
         sampler_descriptor,
     } = params;
 
-    assert_ne!(size.x, 0);
-    assert_ne!(size.y, 0);
-    assert!(usage.contains(
-        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING
-    ));
+    if size.x == 0 || size.y == 0 {
+        return Err(FrameError::ZeroSize { size });
+    }
+    let required =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING;
+    if !usage.contains(required) {
+        return Err(FrameError::MissingUsages {
+            missing: required - usage,
+        });
+    }
 
     let mut image = Image {
         texture_descriptor: TextureDescriptor {
@@ -254,19 +448,21 @@ pub fn create_image(params: CreateImageParams) -> Image {
         texture_view_descriptor: None,
     };
     image.resize(image.texture_descriptor.size); // set image data to 0
-    image
+    Ok(image)
 }
 
 #[allow(rustdoc::broken_intra_doc_links)]
 /// [Plugin group](PluginGroup) that adds the complete `bevy_pixel_buffer`
 /// suite of plugins:
 /// - [PixelBufferPlugin]
+/// - [PixelCursorPlugin](crate::cursor::PixelCursorPlugin)
 /// - [PixelBufferEguiPlugin](crate::egui::PixelBufferEguiPlugin) *requires `egui` feature*
 pub struct PixelBufferPlugins;
 
 impl PluginGroup for PixelBufferPlugins {
     fn build(&mut self, group: &mut bevy::app::PluginGroupBuilder) {
         group.add(PixelBufferPlugin);
+        group.add(super::cursor::PixelCursorPlugin);
 
         #[cfg(feature = "egui")]
         group.add(crate::egui::PixelBufferEguiPlugin);
@@ -278,9 +474,15 @@ pub struct PixelBufferPlugin;
 
 impl Plugin for PixelBufferPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_to_stage(CoreStage::PreUpdate, fill)
+        app.add_event::<PixelBufferResized>()
+            .add_system_to_stage(CoreStage::PreUpdate, fill)
             .add_system_to_stage(CoreStage::PreUpdate, resize.after(fill))
-            .add_system_to_stage(CoreStage::PreUpdate, sprite_custom_size.after(fill));
+            .add_system_to_stage(CoreStage::PreUpdate, sprite_custom_size.after(fill))
+            .add_system_to_stage(CoreStage::PreUpdate, setup_mesh.after(fill))
+            .add_system_to_stage(CoreStage::PreUpdate, mesh_quad_size.after(fill))
+            .add_system_to_stage(CoreStage::PreUpdate, node_size.after(fill));
+
+        fullscreen::build_render_app(app);
     }
 }
 
@@ -288,44 +490,163 @@ impl Plugin for PixelBufferPlugin {
 #[allow(clippy::type_complexity)]
 fn resize(
     pixel_buffer: Query<
-        (&Handle<Image>, &PixelBuffer),
+        (Entity, &Handle<Image>, &PixelBuffer),
         Or<(Changed<PixelBuffer>, Added<Handle<Image>>)>,
     >,
     mut images: ResMut<Assets<Image>>,
+    mut resized_events: EventWriter<PixelBufferResized>,
 ) {
-    for (image, pb) in pixel_buffer.iter() {
-        let PixelBuffer { size, .. } = pb;
+    for (entity, image, pb) in pixel_buffer.iter() {
+        let PixelBuffer {
+            size,
+            resize_policy,
+            ..
+        } = pb;
 
         if size.size.x == 0 || size.size.y == 0 || size.pixel_size.x == 0 || size.pixel_size.y == 0
         {
             warn!("Skipping resize, with and/or height are 0");
-            return;
+            continue;
         }
 
-        let image = images.get_mut(image).expect("pixel buffer image");
-        if size.size != image.size().as_uvec2() {
+        let image = match images.get_mut(image) {
+            Some(image) => image,
+            None => {
+                warn!("Skipping resize, {}", FrameError::MissingImage);
+                continue;
+            }
+        };
+        let old_size = image.size().as_uvec2();
+        if size.size != old_size {
             info!("Resizing image to: {:?}", size);
+            let pixels = resize_pixels(
+                bytemuck::cast_slice(&image.data),
+                old_size,
+                size.size,
+                *resize_policy,
+            );
             image.resize(Extent3d {
                 width: size.size.x,
                 height: size.size.y,
                 depth_or_array_layers: 1,
             });
+            image.data = bytemuck::cast_slice(&pixels).to_vec();
+
+            resized_events.send(PixelBufferResized {
+                entity,
+                old: old_size,
+                new: size.size,
+            });
         }
     }
 }
 
-/// Changes the size of the pixel buffer to match the fill
-fn fill(mut pixel_buffer: Query<&mut PixelBuffer>, windows: Res<Windows>) {
-    for mut pb in pixel_buffer.iter_mut() {
-        if let Some(fill_area) = get_fill_area(&pb, &windows) {
-            let PixelBuffer { size, fill } = pb.as_ref();
+/// Returns the pixels of an image of size `old_size` resized to `new_size` following a [ResizePolicy].
+pub fn resize_pixels(
+    pixels: &[Pixel],
+    old_size: UVec2,
+    new_size: UVec2,
+    policy: ResizePolicy,
+) -> Vec<Pixel> {
+    let mut resized = vec![Pixel::TRANSPARENT; (new_size.x * new_size.y) as usize];
+    if old_size.x == 0 || old_size.y == 0 || pixels.len() != (old_size.x * old_size.y) as usize {
+        return resized;
+    }
+
+    let get = |x: u32, y: u32| pixels[(x + y * old_size.x) as usize];
+
+    match policy {
+        ResizePolicy::Clear => {}
+        ResizePolicy::KeepTopLeft | ResizePolicy::KeepCentered => {
+            // offset of the old content in the new image
+            let offset = if policy == ResizePolicy::KeepCentered {
+                (new_size.as_ivec2() - old_size.as_ivec2()) / 2
+            } else {
+                IVec2::ZERO
+            };
+            for y in 0..new_size.y {
+                for x in 0..new_size.x {
+                    let old = IVec2::new(x as i32, y as i32) - offset;
+                    if old.cmpge(IVec2::ZERO).all() && old.cmplt(old_size.as_ivec2()).all() {
+                        resized[(x + y * new_size.x) as usize] = get(old.x as u32, old.y as u32);
+                    }
+                }
+            }
+        }
+        ResizePolicy::Rescale(filter) => {
+            let scale = old_size.as_vec2() / new_size.as_vec2();
+            for y in 0..new_size.y {
+                for x in 0..new_size.x {
+                    // center of the new pixel in old pixel coordinates
+                    let center = (Vec2::new(x as f32, y as f32) + 0.5) * scale;
+                    let pixel = match filter {
+                        ResizeFilter::Nearest => {
+                            let old = center.as_uvec2().min(old_size - UVec2::ONE);
+                            get(old.x, old.y)
+                        }
+                        ResizeFilter::Bilinear => {
+                            let max = (old_size - UVec2::ONE).as_vec2();
+                            let p = (center - 0.5).clamp(Vec2::ZERO, max);
+                            let p0 = p.floor().as_uvec2();
+                            let p1 = (p0 + UVec2::ONE).min(old_size - UVec2::ONE);
+                            let t = p - p.floor();
+                            let lerp = |a: Vec4, b: Vec4, t: f32| a + (b - a) * t;
+                            let as_vec = |p: Pixel| {
+                                Vec4::new(p.r as f32, p.g as f32, p.b as f32, p.a as f32) / 255.0
+                            };
+                            let top = lerp(as_vec(get(p0.x, p0.y)), as_vec(get(p1.x, p0.y)), t.x);
+                            let bottom =
+                                lerp(as_vec(get(p0.x, p1.y)), as_vec(get(p1.x, p1.y)), t.x);
+                            (lerp(top, bottom, t.y) + 0.5 / 255.0).into()
+                        }
+                    };
+                    resized[(x + y * new_size.x) as usize] = pixel;
+                }
+            }
+        }
+    }
+
+    resized
+}
 
-            let new_buffer_size = fill_area.as_uvec2() / size.pixel_size;
-            // Truncate to the fill multiple
-            let new_buffer_size = (new_buffer_size / fill.multiple) * fill.multiple;
+/// Changes the size of the pixel buffer to match the fill and updates its [FillLayout]
+#[allow(clippy::type_complexity)]
+fn fill(
+    mut commands: Commands,
+    mut pixel_buffer: Query<(
+        Entity,
+        &mut PixelBuffer,
+        Option<&mut FillLayout>,
+        Option<&Node>,
+    )>,
+    fill_areas: FillAreas,
+) {
+    for (entity, mut pb, layout, node) in pixel_buffer.iter_mut() {
+        if let Some(fill_area) = fill_areas.get(&pb, node) {
+            let PixelBuffer { size, fill, .. } = pb.as_ref();
+
+            // only resize mode changes the buffer, the others scale it
+            if fill.mode == FillMode::Resize {
+                let new_buffer_size = fill_area.as_uvec2() / size.pixel_size;
+                // Truncate to the fill multiple
+                let new_buffer_size = (new_buffer_size / fill.multiple) * fill.multiple;
+
+                if new_buffer_size != size.size {
+                    pb.size.size = new_buffer_size;
+                }
+            }
 
-            if new_buffer_size != size.size {
-                pb.size.size = new_buffer_size;
+            let new_layout =
+                FillLayout::compute(&pb.size, pb.fill.mode, pb.fill.stretch, fill_area);
+            match layout {
+                Some(mut layout) => {
+                    if *layout != new_layout {
+                        *layout = new_layout;
+                    }
+                }
+                None => {
+                    commands.entity(entity).insert(new_layout);
+                }
             }
         }
     }
@@ -334,39 +655,77 @@ fn fill(mut pixel_buffer: Query<&mut PixelBuffer>, windows: Res<Windows>) {
 /// Changes the sprite custom size
 #[allow(clippy::type_complexity)]
 fn sprite_custom_size(
-    mut pixel_buffer: Query<(&PixelBuffer, &mut Sprite), Or<(Changed<PixelBuffer>, Added<Sprite>)>>,
-    windows: Res<Windows>,
+    mut pixel_buffer: Query<
+        (&PixelBuffer, Option<&FillLayout>, &mut Sprite),
+        Or<(Changed<PixelBuffer>, Changed<FillLayout>, Added<Sprite>)>,
+    >,
+    fill_areas: FillAreas,
 ) {
-    for (pb, mut sprite) in pixel_buffer.iter_mut() {
-        let mut new_size = pb.size.screen_size().as_vec2();
-
-        // if the sprite needs to stretch
-        if pb.fill.stretch {
-            // set its size to the fill area
-            if let Some(fill_area) = get_fill_area(pb, &windows) {
-                new_size = fill_area;
-            }
-        }
+    for (pb, layout, mut sprite) in pixel_buffer.iter_mut() {
+        let new_size = match layout {
+            // the layout of a buffer that fills an area already accounts for the
+            // fill mode and stretching
+            Some(layout) if pb.fill.kind != FillKind::None => layout.size,
+            _ => fill_areas
+                .get(pb, None)
+                .map(|fill_area| {
+                    FillLayout::compute(&pb.size, pb.fill.mode, pb.fill.stretch, fill_area).size
+                })
+                .unwrap_or_else(|| pb.size.screen_size().as_vec2()),
+        };
 
         info!("Resizing sprite to: {:?}", new_size);
         sprite.custom_size = Some(new_size);
     }
 }
 
-fn get_fill_area(pb: &PixelBuffer, windows: &Windows) -> Option<Vec2> {
-    match pb.fill.kind {
-        FillKind::None => None,
-        FillKind::Window(window_id) => windows
-            .get(window_id)
-            .map(|window| Vec2::new(window.width(), window.height())),
-        FillKind::Custom(custom_size) => Some(custom_size),
+/// Resources needed to get the area a [Fill] covers.
+#[derive(SystemParam)]
+pub(crate) struct FillAreas<'w, 's> {
+    windows: Res<'w, Windows>,
+    cameras: Query<'w, 's, &'static Camera>,
+    images: Res<'w, Assets<Image>>,
+}
+
+impl<'w, 's> FillAreas<'w, 's> {
+    /// Gets the current fill area of a pixel buffer.
+    ///
+    /// `node` is the UI node of the buffer for [FillKind::Node].
+    pub(crate) fn get(&self, pb: &PixelBuffer, node: Option<&Node>) -> Option<Vec2> {
+        match &pb.fill.kind {
+            FillKind::None => None,
+            FillKind::Window(window_id) => self
+                .windows
+                .get(*window_id)
+                .map(|window| Vec2::new(window.width(), window.height())),
+            FillKind::Custom(custom_size) => Some(*custom_size),
+            // the node is not laid out until its first frame
+            FillKind::Node => node
+                .map(|node| node.size)
+                .filter(|size| *size != Vec2::ZERO),
+            FillKind::Camera(camera) => self
+                .cameras
+                .get(*camera)
+                .ok()
+                .and_then(|camera| camera.logical_viewport_size()),
+            FillKind::Image(image) => self
+                .images
+                .get(&Handle::weak(*image))
+                .map(|image| image.size()),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::pixel_buffer::builder::{PixelBufferBuilder, RenderConfig};
     use crate::plugins::pixel_buffer::bundle::{PixelBufferBundle, PixelBufferSpriteBundle};
+    use crate::plugins::pixel_buffer::frame::Frame;
+    use bevy::render::render_resource::TextureFormat;
+    use bevy::ui::{flex_node_system, widget::image_node_system, FlexSurface};
+    use bevy::window::{Window, WindowDescriptor};
+    use raw_window_handle::{RawWindowHandle, WebHandle};
 
     #[test]
     fn do_resize_image() {
@@ -376,7 +735,7 @@ mod tests {
             .add_plugin(bevy::window::WindowPlugin)
             .add_plugin(bevy::render::RenderPlugin);
 
-        app.add_system(resize);
+        app.add_event::<PixelBufferResized>().add_system(resize);
 
         let initial_size = UVec2::new(5, 5);
         let set_size = UVec2::new(10, 10);
@@ -393,6 +752,7 @@ mod tests {
                 pixel_buffer: PixelBuffer {
                     size: PixelBufferSize::size(set_size),
                     fill: Fill::none(),
+                    ..Default::default()
                 },
                 image,
             })
@@ -433,6 +793,7 @@ mod tests {
                 pixel_buffer: PixelBuffer {
                     size: PixelBufferSize::size(set_size),
                     fill: Fill::none(),
+                    ..Default::default()
                 },
                 sprite_bundle: SpriteBundle {
                     sprite: Sprite {
@@ -477,6 +838,7 @@ mod tests {
                 pixel_buffer: PixelBuffer {
                     size: PixelBufferSize::size(set_size),
                     fill: Fill::custom(fill_area),
+                    ..Default::default()
                 },
                 image,
             })
@@ -487,4 +849,248 @@ mod tests {
         let size = app.world.get::<PixelBuffer>(pb_id).unwrap().size.size;
         assert_eq!(size, UVec2::new(10, 10));
     }
+
+    #[test]
+    fn do_fill_integer_scale() {
+        let mut app = App::new();
+
+        app.add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(bevy::window::WindowPlugin)
+            .add_plugin(bevy::render::RenderPlugin);
+
+        app.add_system(fill);
+
+        let set_size = UVec2::new(100, 50);
+        let fill_area = Vec2::new(350.0, 120.0);
+
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let image = images.add(create_image(set_size.into()));
+
+        let pb_id = app
+            .world
+            .spawn()
+            .insert_bundle(PixelBufferBundle {
+                pixel_buffer: PixelBuffer {
+                    size: PixelBufferSize::size(set_size),
+                    fill: Fill::custom(fill_area).with_mode(FillMode::IntegerScale),
+                    ..Default::default()
+                },
+                image,
+            })
+            .id();
+
+        app.update();
+
+        let size = app.world.get::<PixelBuffer>(pb_id).unwrap().size.size;
+        assert_eq!(size, set_size);
+
+        let layout = app.world.get::<FillLayout>(pb_id).unwrap();
+        assert_eq!(layout.scale, Vec2::splat(2.0));
+        assert_eq!(layout.size, Vec2::new(200.0, 100.0));
+        assert_eq!(layout.offset, Vec2::new(75.0, 10.0));
+    }
+
+    #[test]
+    fn aspect_fit_layout() {
+        let size = PixelBufferSize::size((200, 100));
+        let layout =
+            FillLayout::compute(&size, FillMode::AspectFit, false, Vec2::new(100.0, 100.0));
+
+        assert_eq!(layout.size, Vec2::new(100.0, 50.0));
+        assert_eq!(layout.offset, Vec2::new(0.0, 25.0));
+        assert_eq!(layout.area_to_buffer(Vec2::new(50.0, 10.0)), None);
+        assert_eq!(
+            layout.area_to_buffer(Vec2::new(50.0, 50.0)),
+            Some(Vec2::new(100.0, 50.0))
+        );
+    }
+
+    #[test]
+    fn do_fill_image() {
+        let mut app = App::new();
+
+        app.add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(bevy::window::WindowPlugin)
+            .add_plugin(bevy::render::RenderPlugin);
+
+        app.add_system(fill);
+
+        let set_size = UVec2::new(5, 5);
+        let target_size = UVec2::new(30, 20);
+
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let image = images.add(create_image(set_size.into()));
+        let target = images.add(create_image(target_size.into()));
+
+        let pb_id = app
+            .world
+            .spawn()
+            .insert_bundle(PixelBufferBundle {
+                pixel_buffer: PixelBuffer {
+                    size: PixelBufferSize::size(set_size),
+                    fill: Fill::image(&target),
+                    ..Default::default()
+                },
+                image,
+            })
+            .id();
+
+        app.update();
+
+        let size = app.world.get::<PixelBuffer>(pb_id).unwrap().size.size;
+        assert_eq!(size, target_size);
+
+        // the fill follows the target size
+        let new_target_size = UVec2::new(40, 10);
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        images.get_mut(&target).unwrap().resize(Extent3d {
+            width: new_target_size.x,
+            height: new_target_size.y,
+            depth_or_array_layers: 1,
+        });
+
+        app.update();
+
+        let size = app.world.get::<PixelBuffer>(pb_id).unwrap().size.size;
+        assert_eq!(size, new_target_size);
+    }
+
+    #[test]
+    fn resize_policies() {
+        let old_size = UVec2::new(2, 2);
+        let pixels = [Pixel::RED, Pixel::GREEN, Pixel::BLUE, Pixel::WHITE];
+
+        let top_left = resize_pixels(
+            &pixels,
+            old_size,
+            UVec2::new(3, 3),
+            ResizePolicy::KeepTopLeft,
+        );
+        assert_eq!(top_left[0], Pixel::RED);
+        assert_eq!(top_left[4], Pixel::WHITE);
+        assert_eq!(top_left[8], Pixel::TRANSPARENT);
+
+        let centered = resize_pixels(
+            &pixels,
+            old_size,
+            UVec2::new(4, 4),
+            ResizePolicy::KeepCentered,
+        );
+        assert_eq!(centered[0], Pixel::TRANSPARENT);
+        assert_eq!(centered[5], Pixel::RED);
+        assert_eq!(centered[10], Pixel::WHITE);
+
+        let nearest = resize_pixels(
+            &pixels,
+            old_size,
+            UVec2::new(4, 4),
+            ResizePolicy::Rescale(ResizeFilter::Nearest),
+        );
+        assert_eq!(nearest[1], Pixel::RED);
+        assert_eq!(nearest[2], Pixel::GREEN);
+        assert_eq!(nearest[15], Pixel::WHITE);
+
+        let cleared = resize_pixels(&pixels, old_size, UVec2::new(1, 1), ResizePolicy::Clear);
+        assert_eq!(cleared, vec![Pixel::TRANSPARENT]);
+    }
+
+    #[test]
+    fn do_fill_node() {
+        let mut app = App::new();
+
+        app.add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(bevy::window::WindowPlugin)
+            .add_plugin(bevy::render::RenderPlugin);
+
+        // the layout of the UI plugin, without its rendering and text
+        app.init_resource::<FlexSurface>()
+            .add_event::<PixelBufferResized>()
+            .add_system(fill)
+            .add_system(resize.after(fill))
+            .add_system(node_size.after(fill))
+            .add_system(image_node_system.after(resize))
+            .add_system(flex_node_system.after(image_node_system).after(node_size));
+
+        let window_size = UVec2::new(400, 300);
+        app.world.resource_mut::<Windows>().add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: window_size.x as f32,
+                height: window_size.y as f32,
+                ..Default::default()
+            },
+            window_size.x,
+            window_size.y,
+            1.0,
+            None,
+            RawWindowHandle::Web(WebHandle::empty()),
+        ));
+
+        app.add_startup_system(
+            PixelBufferBuilder::new()
+                .with_size(PixelBufferSize {
+                    size: UVec2::new(5, 5),
+                    pixel_size: UVec2::new(2, 2),
+                })
+                .with_fill(Fill::node())
+                .with_render(RenderConfig::ui())
+                .setup(),
+        );
+
+        // the node is laid out on the first frame and filled on the next
+        app.update();
+        app.update();
+
+        // the node does not follow the buffer, so the size is stable
+        for _ in 0..5 {
+            app.update();
+            let pb = app.world.query::<&PixelBuffer>().single(&app.world);
+            assert_eq!(pb.size.size, window_size / 2);
+        }
+    }
+
+    #[test]
+    fn ui_window_fill_fills_the_node() {
+        let mut app = App::new();
+
+        app.add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(bevy::window::WindowPlugin)
+            .add_plugin(bevy::render::RenderPlugin);
+
+        app.add_startup_system(
+            PixelBufferBuilder::new()
+                .with_fill(Fill::window())
+                .with_render(RenderConfig::ui())
+                .setup(),
+        );
+
+        app.update();
+
+        let pb = app.world.query::<&PixelBuffer>().single(&app.world);
+        assert_eq!(pb.fill.kind, FillKind::Node);
+    }
+
+    #[test]
+    fn try_create_image_errors() {
+        assert!(matches!(
+            try_create_image(UVec2::new(0, 5).into()),
+            Err(FrameError::ZeroSize { .. })
+        ));
+
+        let params = CreateImageParams {
+            usage: TextureUsages::TEXTURE_BINDING,
+            ..UVec2::new(5, 5).into()
+        };
+        assert!(matches!(
+            try_create_image(params),
+            Err(FrameError::MissingUsages { .. })
+        ));
+
+        let mut image = try_create_image(UVec2::new(5, 5).into()).unwrap();
+        image.texture_descriptor.format = TextureFormat::R8Unorm;
+        assert!(matches!(
+            Frame::try_get(&mut image),
+            Err(FrameError::WrongFormat { .. })
+        ));
+    }
 }
@@ -51,12 +51,15 @@
 use std::ops::{Deref, DerefMut};
 
 use bevy::{
-    ecs::{query::WorldQuery, system::SystemParam},
+    ecs::{
+        query::{QuerySingleError, WorldQuery},
+        system::SystemParam,
+    },
     prelude::*,
 };
 
 use super::{
-    frame::{AsImageHandle, Frame, GetFrame},
+    frame::{AsImageHandle, Frame, FrameError, GetFrame},
     pixel_buffer::PixelBuffer,
 };
 
@@ -134,8 +137,14 @@ impl<'w, 's> QueryPixelBuffer<'w, 's> {
 }
 
 impl<'w, 's> GetFrame for QueryPixelBuffer<'w, 's> {
-    fn frame(&mut self) -> Frame<'_> {
-        let image_handle = self.query.single().image_handle;
-        Frame::extract(&mut self.images, image_handle)
+    fn try_frame(&mut self) -> Result<Frame<'_>, FrameError> {
+        let image_handle = match self.query.get_single() {
+            Ok(item) => item.image_handle,
+            Err(QuerySingleError::NoEntities(_)) => return Err(FrameError::NoPixelBuffer),
+            Err(QuerySingleError::MultipleEntities(_)) => {
+                return Err(FrameError::MultiplePixelBuffers)
+            }
+        };
+        Frame::try_extract(&mut self.images, image_handle)
     }
 }
@@ -0,0 +1,56 @@
+//! Rendering a pixel buffer as a bevy UI node.
+//!
+//! The pixel buffer entity becomes an image node, so it can be parented to
+//! other UI nodes and laid out next to them.
+//!
+//! Usually set up with [RenderConfig::Ui](crate::builder::RenderConfig::Ui), but
+//! the [PixelBufferNode] component can also be inserted manually next to an
+//! [ImageBundle] and a [PixelBufferBundle](crate::bundle::PixelBufferBundle).
+
+use bevy::prelude::*;
+
+use super::pixel_buffer::{FillKind, PixelBuffer};
+
+/// Marker component of pixel buffers rendered as a UI node.
+///
+/// While the [Fill](crate::pixel_buffer::Fill) of the buffer is not [FillKind::Node],
+/// the node [Style::size] is kept in sync with the screen size of the buffer.
+/// With [FillKind::Node] an [Val::Auto] size becomes 100% of the parent, so the node
+/// does not take the size of its image.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct PixelBufferNode;
+
+/// Changes the node style size to the screen size of the buffer.
+#[allow(clippy::type_complexity)]
+pub(crate) fn node_size(
+    mut pixel_buffer: Query<
+        (&PixelBuffer, &mut Style),
+        (
+            With<PixelBufferNode>,
+            Or<(Changed<PixelBuffer>, Added<PixelBufferNode>)>,
+        ),
+    >,
+) {
+    for (pb, mut style) in pixel_buffer.iter_mut() {
+        // the layout decides the size and the buffer follows it, sized by its image
+        // the node would shrink with the buffer
+        if matches!(pb.fill.kind, FillKind::Node) {
+            let fill = |val| match val {
+                Val::Auto => Val::Percent(100.0),
+                val => val,
+            };
+            let new_size = Size::new(fill(style.size.width), fill(style.size.height));
+            if style.size != new_size {
+                style.size = new_size;
+            }
+            continue;
+        }
+
+        let screen_size = pb.size.screen_size().as_vec2();
+        let new_size = Size::new(Val::Px(screen_size.x), Val::Px(screen_size.y));
+        if style.size != new_size {
+            info!("Resizing node to: {:?}", screen_size);
+            style.size = new_size;
+        }
+    }
+}